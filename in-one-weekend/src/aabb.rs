@@ -0,0 +1,113 @@
+use crate::ray::Ray;
+use crate::Vec3;
+
+/// Axis-aligned bounding box.
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Aabb {
+        Aabb {
+            min: min.inf(&max),
+            max: min.sup(&max),
+        }
+    }
+
+    /// Box containing nothing, neutral for `union`.
+    pub fn empty() -> Aabb {
+        Aabb {
+            min: Vec3::repeat(f64::INFINITY),
+            max: Vec3::repeat(f64::NEG_INFINITY),
+        }
+    }
+
+    pub fn from_points<'p>(points: impl IntoIterator<Item = &'p Vec3>) -> Aabb {
+        points
+            .into_iter()
+            .fold(Aabb::empty(), |aabb, point| aabb.grow(point))
+    }
+
+    pub fn grow(&self, point: &Vec3) -> Aabb {
+        Aabb {
+            min: self.min.inf(point),
+            max: self.max.sup(point),
+        }
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    /// Box common to both, empty if they are disjoint.
+    pub fn intersection(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.sup(&other.min),
+            max: self.max.inf(&other.max),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn centroid(&self) -> Vec3 {
+        0.5 * (self.min + self.max)
+    }
+
+    pub fn extent(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        let extent = self.extent();
+        2.0 * (extent.x * extent.y + extent.y * extent.z + extent.z * extent.x)
+    }
+
+    /// Index of the longest axis.
+    pub fn largest_axis(&self) -> usize {
+        self.extent().imax()
+    }
+
+    /// Slab test, returning the entry parameter.
+    ///
+    /// `inverse` is the component-wise inverse of the ray direction.
+    pub fn hit(&self, min: f64, max: f64, ray: &Ray, inverse: &Vec3) -> Option<f64> {
+        self.clip(min, max, ray, inverse).map(|(entry, _)| entry)
+    }
+
+    /// Slab test, returning the entry and exit parameters.
+    pub fn clip(&self, min: f64, max: f64, ray: &Ray, inverse: &Vec3) -> Option<(f64, f64)> {
+        let mut min = min;
+        let mut max = max;
+
+        for axis in 0..3 {
+            let t0 = (self.min[axis] - ray.origin[axis]) * inverse[axis];
+            let t1 = (self.max[axis] - ray.origin[axis]) * inverse[axis];
+            let (t0, t1) = if inverse[axis].is_sign_negative() {
+                (t1, t0)
+            } else {
+                (t0, t1)
+            };
+
+            // NaN (0 * inf) leaves the bounds untouched
+            min = if t0 > min { t0 } else { min };
+            max = if t1 < max { t1 } else { max };
+
+            if max < min {
+                return None;
+            }
+        }
+
+        Some((min, max))
+    }
+}
@@ -0,0 +1,77 @@
+use crate::material::Phase;
+use crate::ray::Ray;
+
+/// Participating medium filling the whole scene, below a ceiling.
+///
+/// Its density is `density` at height 0 and is divided by e every
+/// `1 / falloff` units up, a null `falloff` giving a uniform haze.
+pub struct Atmosphere {
+    density: f64,
+    falloff: f64,
+    ceiling: f64,
+    pub phase: Phase,
+}
+
+impl Atmosphere {
+    /// Same density everywhere below `ceiling`.
+    pub fn haze(density: f64, ceiling: f64, phase: Phase) -> Atmosphere {
+        Atmosphere {
+            density,
+            falloff: 0.0,
+            ceiling,
+            phase,
+        }
+    }
+
+    /// Exponential height fog, thinning out with altitude.
+    pub fn fog(density: f64, falloff: f64, phase: Phase) -> Atmosphere {
+        Atmosphere {
+            density,
+            falloff,
+            ceiling: f64::INFINITY,
+            phase,
+        }
+    }
+
+    /// Parameter of the next collision of `ray` with the medium before `max`.
+    pub fn collide(&self, ray: &Ray, max: f64) -> Option<f64> {
+        let (origin, direction) = (ray.origin.y, ray.direction.y);
+
+        // Part of the ray below the ceiling
+        let (start, end) = match (origin < self.ceiling, direction > 0.0) {
+            _ if direction == 0.0 => (0.0, if origin < self.ceiling { max } else { 0.0 }),
+            (true, true) => (0.0, max.min((self.ceiling - origin) / direction)),
+            (true, false) => (0.0, max),
+            (false, true) => return None,
+            (false, false) => ((self.ceiling - origin) / direction, max),
+        };
+        if start >= end {
+            return None;
+        }
+
+        // Optical depth to reach, and the one gathered per unit of parameter
+        // from `start` if the density were constant
+        let depth = -(1.0 - rand::random::<f64>()).ln();
+        let rate = self.density
+            * (-self.falloff * (origin + start * direction)).exp()
+            * ray.direction.norm();
+        if rate <= 0.0 {
+            return None;
+        }
+
+        let slope = self.falloff * direction;
+        let distance = if slope.abs() < 1e-9 {
+            depth / rate
+        } else {
+            // The optical depth of rays rising is bounded
+            let left = 1.0 - depth * slope / rate;
+            if left <= 0.0 {
+                return None;
+            }
+            -left.ln() / slope
+        };
+
+        let root = start + distance;
+        (root < end).then_some(root)
+    }
+}
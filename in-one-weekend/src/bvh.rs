@@ -0,0 +1,320 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::hit::Hit;
+use crate::ray::Ray;
+use crate::Vec3;
+
+use std::fmt;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Number of buckets centroids are binned into along the split axis.
+const BINS: usize = 16;
+/// Cost of visiting a node, relative to intersecting a primitive.
+const TRAVERSAL: f64 = 0.125;
+/// Leaves are always split above this size.
+const MAX_LEAF: usize = 8;
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Leaf {
+        first: usize,
+        count: usize,
+    },
+    /// The first child directly follows its parent.
+    Interior {
+        second: usize,
+        axis: usize,
+    },
+}
+
+#[derive(Clone, Copy)]
+struct Node {
+    bounds: Aabb,
+    kind: Kind,
+}
+
+/// Bounding-volume hierarchy over primitive indices, built with the surface
+/// area heuristic.
+pub struct Tree {
+    nodes: Vec<Node>,
+    /// Primitive indices, leaves referencing contiguous ranges.
+    indices: Vec<usize>,
+}
+
+impl Tree {
+    /// Builds the tree over primitives given by their bounds.
+    pub fn new(bounds: &[Aabb]) -> Tree {
+        let mut tree = Tree {
+            nodes: Vec::with_capacity(2 * bounds.len()),
+            indices: (0..bounds.len()).collect(),
+        };
+
+        if !bounds.is_empty() {
+            let centroids: Vec<Vec3> = bounds.iter().map(Aabb::centroid).collect();
+            tree.build(bounds, &centroids, 0, bounds.len());
+        }
+
+        tree
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        self.nodes
+            .first()
+            .map_or_else(Aabb::empty, |node| node.bounds)
+    }
+
+    fn build(&mut self, bounds: &[Aabb], centroids: &[Vec3], first: usize, end: usize) -> usize {
+        let range = &mut self.indices[first..end];
+        let count = range.len();
+        let node_bounds = range
+            .iter()
+            .fold(Aabb::empty(), |aabb, &i| aabb.union(&bounds[i]));
+        let centroid_bounds = Aabb::from_points(range.iter().map(|&i| &centroids[i]));
+
+        let index = self.nodes.len();
+        let leaf = Node {
+            bounds: node_bounds,
+            kind: Kind::Leaf { first, count },
+        };
+        self.nodes.push(leaf);
+
+        let axis = centroid_bounds.largest_axis();
+        let low = centroid_bounds.min[axis];
+        let extent = centroid_bounds.extent()[axis];
+        if count <= 1 || extent <= 0.0 {
+            return index;
+        }
+
+        let bin = |i: usize| {
+            let offset = (centroids[i][axis] - low) / extent;
+            ((offset * BINS as f64) as usize).min(BINS - 1)
+        };
+
+        let mut counts = [0; BINS];
+        let mut boxes = [Aabb::empty(); BINS];
+        for &i in range.iter() {
+            let b = bin(i);
+            counts[b] += 1;
+            boxes[b] = boxes[b].union(&bounds[i]);
+        }
+
+        // Cost of splitting after each bin
+        let mut best = (f64::INFINITY, 0);
+        for split in 0..BINS - 1 {
+            let (left, right) = (&boxes[..=split], &boxes[split + 1..]);
+            let left_count: usize = counts[..=split].iter().sum();
+            let right_count = count - left_count;
+            if left_count == 0 || right_count == 0 {
+                continue;
+            }
+
+            let left_area = left
+                .iter()
+                .fold(Aabb::empty(), |a, b| a.union(b))
+                .surface_area();
+            let right_area = right
+                .iter()
+                .fold(Aabb::empty(), |a, b| a.union(b))
+                .surface_area();
+            let cost = TRAVERSAL
+                + (left_area * left_count as f64 + right_area * right_count as f64)
+                    / node_bounds.surface_area();
+
+            if cost < best.0 {
+                best = (cost, split);
+            }
+        }
+
+        let (cost, split) = best;
+        if cost >= count as f64 && count <= MAX_LEAF {
+            return index;
+        }
+
+        let mut middle = itertools::partition(range.iter_mut(), |&i| bin(i) <= split);
+        if middle == 0 || middle == count {
+            middle = count / 2;
+        }
+        let middle = first + middle;
+
+        self.build(bounds, centroids, first, middle);
+        let second = self.build(bounds, centroids, middle, end);
+        self.nodes[index].kind = Kind::Interior { second, axis };
+
+        index
+    }
+
+    /// Finds the closest impact, `f` hitting the primitive of a given index.
+    pub fn hit<'h, F>(&self, min: f64, mut max: f64, ray: &Ray, mut f: F) -> Option<hit::Impact<'h>>
+    where
+        F: FnMut(usize, f64, f64) -> Option<hit::Impact<'h>>,
+    {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let inverse = ray.direction.map(f64::recip);
+        let mut closest = None;
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0);
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.bounds.hit(min, max, ray, &inverse).is_none() {
+                continue;
+            }
+
+            match node.kind {
+                Kind::Leaf { first, count } => {
+                    for &i in &self.indices[first..first + count] {
+                        if let Some(impact) = f(i, min, max) {
+                            max = impact.parameter();
+                            closest = Some(impact);
+                        }
+                    }
+                }
+                // Visit the nearest child first
+                Kind::Interior { second, axis } => {
+                    if ray.direction[axis].is_sign_negative() {
+                        stack.push(index + 1);
+                        stack.push(second);
+                    } else {
+                        stack.push(second);
+                        stack.push(index + 1);
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            primitives: self.indices.len(),
+            ..Stats::default()
+        };
+
+        if self.nodes.is_empty() {
+            return stats;
+        }
+
+        let root = self.bounds().surface_area().max(f64::MIN_POSITIVE);
+        let mut stack = vec![(0, 1)];
+        while let Some((index, depth)) = stack.pop() {
+            let node = &self.nodes[index];
+            let area = node.bounds.surface_area() / root;
+            stats.nodes += 1;
+            stats.depth = stats.depth.max(depth);
+
+            match node.kind {
+                Kind::Leaf { count, .. } => {
+                    stats.leaves += 1;
+                    stats.max_leaf = stats.max_leaf.max(count);
+                    stats.cost += area * count as f64;
+                }
+                Kind::Interior { second, .. } => {
+                    stats.cost += area * TRAVERSAL;
+                    stack.push((index + 1, depth + 1));
+                    stack.push((second, depth + 1));
+                }
+            }
+        }
+
+        stats
+    }
+}
+
+#[derive(Default)]
+pub struct Stats {
+    pub primitives: usize,
+    pub unbounded: usize,
+    pub nodes: usize,
+    pub leaves: usize,
+    pub depth: usize,
+    pub max_leaf: usize,
+    /// Expected cost of a ray according to the surface area heuristic.
+    pub cost: f64,
+    pub time: Duration,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "primitives: {}", self.primitives)?;
+        writeln!(f, "unbounded:  {}", self.unbounded)?;
+        writeln!(f, "nodes:      {}", self.nodes)?;
+        writeln!(f, "leaves:     {}", self.leaves)?;
+        writeln!(f, "depth:      {}", self.depth)?;
+        writeln!(f, "max leaf:   {}", self.max_leaf)?;
+        writeln!(f, "SAH cost:   {:.3}", self.cost)?;
+        write!(f, "build time: {:?}", self.time)
+    }
+}
+
+/// `Hit` collection accelerated by a `Tree`.
+///
+/// Hitables without bounds are tested separately for every ray.
+pub struct Bvh<T> {
+    hitables: Vec<T>,
+    tree: Tree,
+    unbounded: Vec<T>,
+    time: Duration,
+}
+
+impl<T> Bvh<T>
+where
+    T: Hit,
+{
+    pub fn new(hitables: Vec<T>) -> Bvh<T> {
+        let start = Instant::now();
+
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = hitables
+            .into_iter()
+            .map(|hitable| (hitable.bounding_box(), hitable))
+            .partition(|(bounds, _)| bounds.is_some());
+
+        let (bounds, hitables): (Vec<_>, Vec<_>) = bounded
+            .into_iter()
+            .map(|(bounds, hitable)| (bounds.unwrap(), hitable))
+            .unzip();
+        let tree = Tree::new(&bounds);
+        let unbounded = unbounded.into_iter().map(|(_, hitable)| hitable).collect();
+
+        Bvh {
+            hitables,
+            tree,
+            unbounded,
+            time: start.elapsed(),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            unbounded: self.unbounded.len(),
+            time: self.time,
+            ..self.tree.stats()
+        }
+    }
+}
+
+impl<T> Hit for Bvh<T>
+where
+    T: Hit,
+{
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let bounded = self.tree.hit(min, max, ray, |i, min, max| {
+            self.hitables[i].hit(min, max, ray)
+        });
+        let max = bounded.as_ref().map_or(max, hit::Impact::parameter);
+
+        self.unbounded.hit(min, max, ray).or(bounded)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        if !self.unbounded.is_empty() {
+            return None;
+        }
+
+        Some(self.tree.bounds())
+    }
+}
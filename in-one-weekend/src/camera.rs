@@ -1,4 +1,5 @@
 use crate::image::Pixel;
+use crate::ray::Differential;
 use crate::ray::Ray;
 use crate::Vec3;
 
@@ -11,6 +12,8 @@ pub struct Camera {
     vertical: Vec3,
     origin: Vec3,
     lens_radius: f64,
+    /// Times the shutter opens and closes.
+    shutter: (f64, f64),
 }
 
 impl Camera {
@@ -46,11 +49,20 @@ impl Camera {
             vertical,
             origin,
             lens_radius: 0.5 * aperture,
+            shutter: (0.0, 0.0),
         }
     }
 
-    /// Gathers `Ray` (light) for the given `Camera` `Pixel`.
-    pub fn gather(&self, pixel: Pixel) -> Ray {
+    /// Keeps the shutter open from `open` to `close`, rays being spread over
+    /// that interval.
+    pub fn with_shutter(mut self, open: f64, close: f64) -> Self {
+        self.shutter = (open, close);
+        self
+    }
+
+    /// Gathers `Ray` (light) for the given `Camera` `Pixel`, its differentials
+    /// going through the pixels `spacing` away.
+    pub fn gather(&self, pixel: Pixel, spacing: Pixel) -> Ray {
         let random = self.lens_radius * random_on_unit_disk();
         let offset = random.x * self.u + random.y * self.v;
         let origin = self.origin + offset;
@@ -60,7 +72,16 @@ impl Camera {
         direction += pixel.y * self.vertical;
         direction -= origin;
 
-        Ray::new(origin, direction)
+        let (open, close) = self.shutter;
+        let time = open + rand::random::<f64>() * (close - open);
+
+        let differentials =
+            [spacing.x * self.horizontal, spacing.y * self.vertical].map(|step| Differential {
+                origin,
+                direction: (direction + step).normalize(),
+            });
+
+        Ray::new(origin, direction, time).with_differentials(differentials)
     }
 }
 
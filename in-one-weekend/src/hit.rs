@@ -1,11 +1,15 @@
 use derive_new::new;
 
+use crate::aabb::Aabb;
 use crate::material::Material;
 use crate::material::Scattered;
+use crate::na;
 use crate::ray::Ray;
+use crate::shape::Uv;
 use crate::Vec3;
 
 use std::cmp::Ordering;
+use std::sync::Arc;
 
 trait InspectOption<T> {
     fn _inspect<F>(self, f: F) -> Option<T>
@@ -25,7 +29,7 @@ impl<T> InspectOption<T> for Option<T> {
     }
 }
 
-pub trait Hit {
+pub trait Hit: Send + Sync {
     fn boxed(self) -> Box<dyn Hit>
     where
         Self: Sized + 'static,
@@ -34,6 +38,9 @@ pub trait Hit {
     }
 
     fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<Impact<'_>>;
+
+    /// `None` if unbounded.
+    fn bounding_box(&self) -> Option<Aabb>;
 }
 
 #[derive(new)]
@@ -42,21 +49,112 @@ pub struct Impact<'m> {
     pub point: Vec3,
     pub normal: Vec3,
     material: &'m dyn Material,
+    /// Point in the space of the innermost transformed or moving object,
+    /// for solid textures to stick to it.
+    #[new(value = "point")]
+    pub local: Vec3,
+    /// Texture coordinates, if the shape has any.
+    #[new(default)]
+    pub uv: Option<Uv>,
+    /// Color interpolated from the vertices, if the shape has any.
+    #[new(default)]
+    pub color: Option<Vec3>,
+    /// Direction along the surface, such as the axis of a fiber.
+    #[new(default)]
+    pub tangent: Option<Vec3>,
+    /// How the normal turns as the point moves, `dn/dp`, zero on flat
+    /// surfaces, if the shape tells.
+    #[new(default)]
+    pub curvature: Option<na::Matrix3<f64>>,
+    /// Rates of change of the point along `u` and `v`.
+    #[new(default)]
+    pub derivatives: Option<[Vec3; 2]>,
+    /// Offsets to the points seen through the next pixels along x and y.
+    #[new(default)]
+    pub spread: Option<[Vec3; 2]>,
+    /// Same offsets in texture coordinates.
+    #[new(default)]
+    pub footprint: Option<[Uv; 2]>,
 }
 
 impl Impact<'_> {
+    pub fn parameter(&self) -> f64 {
+        self.parameter
+    }
+
+    /// Normal facing against the incident `direction`.
+    pub fn facing(&self, direction: &Vec3) -> Vec3 {
+        if direction.dot(&self.normal).is_sign_positive() {
+            -self.normal
+        } else {
+            self.normal
+        }
+    }
+
+    /// Finds the `spread` and `footprint` of the impact from the
+    /// differentials of `ray`, meeting the plane tangent to the surface.
+    pub fn differentiate(&mut self, ray: &Ray) {
+        let differentials = match &ray.differentials {
+            Some(differentials) => differentials,
+            None => return,
+        };
+
+        let spread = differentials.map(|differential| {
+            let cosine = self.normal.dot(&differential.direction);
+            let distance = self.normal.dot(&(self.point - differential.origin)) / cosine;
+            differential.origin + distance * differential.direction - self.point
+        });
+        if spread
+            .iter()
+            .any(|offset| !offset.iter().all(|x| x.is_finite()))
+        {
+            return;
+        }
+        self.spread = Some(spread);
+
+        // Least squares solution of the offsets along the derivatives
+        if let Some([u, v]) = &self.derivatives {
+            let basis = na::Matrix3x2::from_columns(&[*u, *v]);
+            if let Some(inverse) = (basis.transpose() * basis).try_inverse() {
+                let projection = inverse * basis.transpose();
+                self.footprint = Some(spread.map(|offset| projection * offset));
+            }
+        }
+    }
+
     pub fn scatter(&self, ray: Ray) -> Option<Scattered> {
         self.material.scatter(ray, self)
     }
+
+    pub fn emitted(&self) -> Vec3 {
+        self.material.emitted(self)
+    }
 }
 
 impl<T> Hit for Box<T>
 where
-    T: Hit,
+    T: Hit + ?Sized,
 {
     fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<Impact<'_>> {
         (**self).hit(min, max, ray)
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        (**self).bounding_box()
+    }
+}
+
+impl<T> Hit for Arc<T>
+where
+    T: Hit + ?Sized,
+{
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<Impact<'_>> {
+        (**self).hit(min, max, ray)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        (**self).bounding_box()
+    }
 }
 
 impl<T> Hit for Vec<T>
@@ -76,4 +174,10 @@ where
                     .unwrap_or(Ordering::Equal)
             })
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.iter()
+            .map(Hit::bounding_box)
+            .try_fold(Aabb::empty(), |aabb, bounds| Some(aabb.union(&bounds?)))
+    }
 }
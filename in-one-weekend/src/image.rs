@@ -79,19 +79,22 @@ impl Image {
             .flat_map(|j| {
                 let width = f64::from(self.width - 1);
                 let height = f64::from(self.height - 1);
+                // Between pixels, narrowed as samples get denser
+                let spread = f64::from(sampling).sqrt().recip().max(0.125);
+                let spacing = spread * Pixel::new(width.recip(), height.recip());
 
                 (0..self.width).into_par_iter().map(move |i| {
                     let color: Vec3 = (0..sampling)
                         .map(|_| {
                             let u = (f64::from(i) + rand::random::<f64>()) / width;
                             let v = (f64::from(j) + rand::random::<f64>()) / height;
-                            scene.sample(camera, Pixel::new(u, v))
+                            scene.sample(camera, Pixel::new(u, v), spacing)
                         })
                         .sum();
 
                     let mut color = color / f64::from(sampling);
                     // Gamma correction
-                    color.apply(|x| *x = x.sqrt());
+                    color.apply(|x| *x = x.sqrt().min(1.0));
                     let color: na::Vector3<u8> = na::try_convert(255.0 * color).unwrap();
 
                     vec![color.x, color.y, color.z]
@@ -0,0 +1,98 @@
+mod gltf;
+mod grid;
+mod heightmap;
+mod obj;
+mod ply;
+mod texture;
+
+pub use crate::load::gltf::*;
+pub use crate::load::grid::*;
+pub use crate::load::heightmap::*;
+pub use crate::load::obj::*;
+pub use crate::load::ply::*;
+pub use crate::load::texture::*;
+
+use crate::camera::Camera;
+use crate::hit::Hit;
+use crate::scene::Scene;
+use crate::shape::Tessellation;
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// A scene with its own camera, if the format has any.
+pub type Loaded = (Scene<Box<dyn Hit>>, Option<Camera>);
+
+/// Loads a scene, choosing the format from the file extension.
+///
+/// Models without cameras (OBJ and PLY) are placed on a ground, OBJ
+/// displacement maps applied after `tessellation`.
+pub fn load(
+    path: impl AsRef<Path>,
+    aspect: f64,
+    tessellation: Tessellation,
+) -> Result<Loaded, Error> {
+    let path = path.as_ref();
+    let extension = path.extension().and_then(|extension| extension.to_str());
+
+    match extension.map(str::to_ascii_lowercase).as_deref() {
+        Some("obj") => {
+            let meshes = load_obj(path, tessellation)?;
+            let model = meshes.into_iter().map(Hit::boxed).collect();
+            Ok((Scene::model(model), None))
+        }
+        Some("ply") => Ok((Scene::model(load_ply(path)?), None)),
+        Some("gltf") | Some("glb") => {
+            let (scene, camera) = load_gltf(path, aspect)?;
+            Ok((scene, Some(camera)))
+        }
+        _ => Err(Error::Unsupported(path.to_owned())),
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, io::Error),
+    /// Unknown file extension.
+    Unsupported(PathBuf),
+    Gltf(PathBuf, ::gltf::Error),
+    Png(PathBuf, png::DecodingError),
+    /// Malformed content without line information.
+    Invalid(PathBuf, String),
+    /// Malformed content at a 1-based line number.
+    Parse(PathBuf, usize, String),
+}
+
+impl Error {
+    fn parse(path: &Path, line: usize, message: impl Into<String>) -> Error {
+        Error::Parse(path.to_owned(), line, message.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            Error::Unsupported(path) => write!(f, "{}: unsupported format", path.display()),
+            Error::Gltf(path, error) => write!(f, "{}: {}", path.display(), error),
+            Error::Png(path, error) => write!(f, "{}: {}", path.display(), error),
+            Error::Invalid(path, message) => write!(f, "{}: {}", path.display(), message),
+            Error::Parse(path, line, message) => {
+                write!(f, "{}:{}: {}", path.display(), line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(_, error) => Some(error),
+            Error::Gltf(_, error) => Some(error),
+            Error::Png(_, error) => Some(error),
+            Error::Unsupported(_) | Error::Invalid(..) | Error::Parse(..) => None,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::camera::Camera;
+use crate::hit::Hit;
+use crate::load::Error;
+use crate::material::Dielectric;
+use crate::material::Lambertian;
+use crate::material::Material;
+use crate::material::Metal;
+use crate::na;
+use crate::scene::Scene;
+use crate::shape::Face;
+use crate::shape::Mesh;
+use crate::shape::Uv;
+use crate::texture;
+use crate::texture::Bitmap;
+use crate::texture::Filter;
+use crate::texture::Texture;
+use crate::texture::Wrap;
+use crate::Vec3;
+
+use std::path::Path;
+
+type Matrix4 = na::Matrix4<f64>;
+
+/// Buffers and decoded images of the file.
+type Data<'a> = (&'a [gltf::buffer::Data], &'a [gltf::image::Data]);
+
+/// Everything collected while walking the node hierarchy.
+#[derive(Default)]
+struct Walk {
+    meshes: Vec<Box<dyn Hit>>,
+    camera: Option<Camera>,
+    min: Option<Vec3>,
+    max: Option<Vec3>,
+}
+
+/// Loads a glTF 2.0 (`.gltf` or `.glb`) scene.
+///
+/// Every triangle primitive becomes a `Mesh` in world space. The first
+/// perspective camera found is used, otherwise a camera framing the whole
+/// scene is made.
+pub fn load_gltf(
+    path: impl AsRef<Path>,
+    aspect: f64,
+) -> Result<(Scene<Box<dyn Hit>>, Camera), Error> {
+    let path = path.as_ref();
+    let (document, buffers, images) =
+        gltf::import(path).map_err(|error| Error::Gltf(path.to_owned(), error))?;
+
+    let scene = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or_else(|| Error::Invalid(path.to_owned(), "no scene".to_owned()))?;
+
+    let mut walk = Walk::default();
+    for node in scene.nodes() {
+        let data = (buffers.as_slice(), images.as_slice());
+        visit(&node, &Matrix4::identity(), data, aspect, &mut walk);
+    }
+
+    let camera = match (walk.camera, walk.min, walk.max) {
+        (Some(camera), _, _) => camera,
+        (None, Some(min), Some(max)) => frame(min, max, aspect),
+        (None, _, _) => return Err(Error::Invalid(path.to_owned(), "empty scene".to_owned())),
+    };
+
+    Ok((Scene::new(walk.meshes), camera))
+}
+
+fn visit(node: &gltf::Node<'_>, parent: &Matrix4, data: Data<'_>, aspect: f64, walk: &mut Walk) {
+    let local = na::Matrix4::from(node.transform().matrix()).cast::<f64>();
+    let transform = parent * local;
+
+    if let Some(mesh) = node.mesh() {
+        for primitive in mesh.primitives() {
+            if let Some(mesh) = primitive_to_mesh(&primitive, &transform, data, walk) {
+                walk.meshes.push(mesh.boxed());
+            }
+        }
+    }
+
+    if let (Some(camera), None) = (node.camera(), &walk.camera) {
+        if let gltf::camera::Projection::Perspective(perspective) = camera.projection() {
+            // Cameras look down -Z with +Y up
+            let origin = transform.transform_point(&na::Point3::origin()).coords;
+            let forward = transform.transform_vector(&-Vec3::z());
+            let up = transform.transform_vector(&Vec3::y());
+            // Vertical field of view, the image setting the aspect ratio
+            let fov = f64::from(perspective.yfov()).to_degrees();
+
+            walk.camera = Some(Camera::new(
+                origin,
+                origin + forward,
+                up,
+                fov,
+                aspect,
+                0.0,
+                forward.norm(),
+            ));
+        }
+    }
+
+    for child in node.children() {
+        visit(&child, &transform, data, aspect, walk);
+    }
+}
+
+fn primitive_to_mesh(
+    primitive: &gltf::Primitive<'_>,
+    transform: &Matrix4,
+    (buffers, images): Data<'_>,
+    walk: &mut Walk,
+) -> Option<Mesh> {
+    if primitive.mode() != gltf::mesh::Mode::Triangles {
+        return None;
+    }
+
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+    let positions: Vec<Vec3> = reader
+        .read_positions()?
+        .map(|[x, y, z]| Vec3::new(x.into(), y.into(), z.into()))
+        .map(|position| transform.transform_point(&position.into()).coords)
+        .collect();
+
+    // Normals transform with the inverse transpose
+    let normal_transform = transform
+        .fixed_slice::<3, 3>(0, 0)
+        .try_inverse()
+        .unwrap_or_else(na::Matrix3::identity)
+        .transpose();
+    let normals: Vec<Vec3> = reader
+        .read_normals()
+        .map(|normals| {
+            normals
+                .map(|[x, y, z]| normal_transform * Vec3::new(x.into(), y.into(), z.into()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let uvs: Vec<Uv> = reader
+        .read_tex_coords(0)
+        .map(|uvs| {
+            uvs.into_f32()
+                // Images start from their top row
+                .map(|[u, v]| Uv::new(u.into(), 1.0 - f64::from(v)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let indices: Vec<usize> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    for position in &positions {
+        walk.min = Some(walk.min.map_or(*position, |min| min.inf(position)));
+        walk.max = Some(walk.max.map_or(*position, |max| max.sup(position)));
+    }
+
+    let has_normals = normals.len() == positions.len();
+    let has_uvs = uvs.len() == positions.len();
+    let faces = indices
+        .chunks_exact(3)
+        .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+        .filter(|triangle| triangle.iter().all(|&i| i < positions.len()))
+        .map(|triangle| {
+            let normals = has_normals.then_some(triangle);
+            let uvs = has_uvs.then_some(triangle);
+            Face::new(triangle, normals, uvs, 0)
+        })
+        .collect();
+
+    let material = material(&primitive.material(), images);
+
+    Some(Mesh::new(positions, normals, uvs, faces, vec![material]))
+}
+
+/// Maps a PBR metallic-roughness material to the closest available one.
+fn material(material: &gltf::Material<'_>, images: &[gltf::image::Data]) -> Box<dyn Material> {
+    let pbr = material.pbr_metallic_roughness();
+    let [r, g, b, alpha] = pbr.base_color_factor().map(f64::from);
+    let factor = Vec3::new(r, g, b);
+
+    // Only the first set of texture coordinates is read
+    let texture = pbr
+        .base_color_texture()
+        .filter(|info| info.tex_coord() == 0)
+        .and_then(|info| {
+            let texture = info.texture();
+            let bitmap = bitmap(&images[texture.source().index()], &factor)?;
+            Some(sampled(bitmap, &texture.sampler()))
+        });
+    let color = match texture {
+        Some(bitmap) => bitmap.boxed(),
+        None => factor.boxed(),
+    };
+
+    let transmission = material
+        .transmission()
+        .map_or(0.0, |transmission| transmission.transmission_factor());
+    let blended = material.alpha_mode() == gltf::material::AlphaMode::Blend && alpha < 1.0;
+    if transmission > 0.5 || blended {
+        let index = material.ior().map_or(1.5, f64::from);
+        return Dielectric::new(color, index).boxed();
+    }
+
+    if pbr.metallic_factor() >= 0.5 {
+        let fuzz = f64::from(pbr.roughness_factor()).clamp(0.0, 0.99);
+        return Metal::new(color, fuzz).boxed();
+    }
+
+    Lambertian::new(color).boxed()
+}
+
+/// Linear colors of an 8-bit sRGB image, tinted by `factor`.
+fn bitmap(image: &gltf::image::Data, factor: &Vec3) -> Option<Bitmap> {
+    use gltf::image::Format;
+
+    let channels = match image.format {
+        Format::R8 => 1,
+        Format::R8G8 => 2,
+        Format::R8G8B8 => 3,
+        Format::R8G8B8A8 => 4,
+        _ => return None,
+    };
+
+    let linear = |value: u8| texture::srgb_to_linear(f64::from(value) / f64::from(u8::MAX));
+    let texels = image
+        .pixels
+        .chunks_exact(channels)
+        .map(|pixel| {
+            let color = match pixel {
+                [gray] | [gray, _] => Vec3::repeat(linear(*gray)),
+                [r, g, b, ..] => Vec3::new(linear(*r), linear(*g), linear(*b)),
+                _ => unreachable!(),
+            };
+            color.component_mul(factor)
+        })
+        .collect();
+
+    Some(Bitmap::new(
+        image.width as usize,
+        image.height as usize,
+        texels,
+    ))
+}
+
+/// Applies the wrap modes and magnification filter of `sampler`.
+fn sampled(bitmap: Bitmap, sampler: &gltf::texture::Sampler<'_>) -> Bitmap {
+    use gltf::texture::MagFilter;
+    use gltf::texture::MinFilter;
+    use gltf::texture::WrappingMode;
+
+    let wrap = |mode| match mode {
+        WrappingMode::Repeat => Wrap::Repeat,
+        WrappingMode::ClampToEdge => Wrap::Clamp,
+        WrappingMode::MirroredRepeat => Wrap::Mirror,
+    };
+    let filter = match (sampler.mag_filter(), sampler.min_filter()) {
+        (Some(MagFilter::Nearest), _) => Filter::Nearest,
+        (_, Some(MinFilter::Nearest | MinFilter::Linear)) => Filter::Bilinear,
+        _ => Filter::Trilinear,
+    };
+
+    bitmap
+        .with_wrap(wrap(sampler.wrap_s()), wrap(sampler.wrap_t()))
+        .with_filter(filter)
+}
+
+/// Camera looking at the bounding box from its front (+Z) side, a box
+/// shrunk to a point framed as a unit ball.
+fn frame(min: Vec3, max: Vec3, aspect: f64) -> Camera {
+    const FOV: f64 = 40.0;
+
+    let center = 0.5 * (min + max);
+    let radius = match 0.5 * (max - min).norm() {
+        radius if radius > 0.0 => radius,
+        _ => 1.0,
+    };
+    let distance = radius / (0.5 * FOV.to_radians()).sin();
+    let origin = center + distance * Vec3::new(0.0, 0.3, 1.0).normalize();
+
+    Camera::new(origin, center, Vec3::y(), FOV, aspect, 0.0, distance)
+}
@@ -0,0 +1,69 @@
+use crate::load::Error;
+use crate::shape::Grid;
+
+use std::fs;
+use std::path::Path;
+
+/// Loads a voxel grid from a little-endian binary file:
+///
+/// - the `VGRD` magic bytes,
+/// - the width, height and depth as `u32`,
+/// - the number of channels as `u32`, 1 for densities alone and 2 for
+///   densities and temperatures,
+/// - the voxels as `f32`, channels interleaved, `x` varying fastest then `y`.
+pub fn load_grid(path: impl AsRef<Path>) -> Result<Grid, Error> {
+    let path = path.as_ref();
+    let invalid = |message: String| Error::Invalid(path.to_owned(), message);
+
+    let bytes = fs::read(path).map_err(|error| Error::Io(path.to_owned(), error))?;
+    if bytes.len() < 20 || &bytes[..4] != b"VGRD" {
+        return Err(invalid("not a voxel grid".to_owned()));
+    }
+
+    let mut header = bytes[4..20]
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]) as usize);
+    let size = [(); 3].map(|_| header.next().unwrap_or_default());
+    let channels = header.next().unwrap_or_default();
+
+    if size.contains(&0) {
+        return Err(invalid(format!("empty grid of {:?} voxels", size)));
+    }
+    if !matches!(channels, 1 | 2) {
+        return Err(invalid(format!("{} channels instead of 1 or 2", channels)));
+    }
+
+    let length = size
+        .iter()
+        .chain([&channels, &4])
+        .try_fold(1, |length: usize, &factor| length.checked_mul(factor))
+        .ok_or_else(|| invalid(format!("too large grid of {:?} voxels", size)))?;
+    let voxels = &bytes[20..];
+    if voxels.len() != length {
+        let message = format!("{} bytes of voxels, expected {}", voxels.len(), length);
+        return Err(invalid(message));
+    }
+
+    let values: Vec<f64> = voxels
+        .chunks_exact(4)
+        .map(|value| f64::from(f32::from_le_bytes([value[0], value[1], value[2], value[3]])))
+        .collect();
+    // Non-finite values would stall the sampling of the volume
+    if let Some(value) = values
+        .iter()
+        .find(|value| !(value.is_finite() && **value >= 0.0))
+    {
+        return Err(invalid(format!(
+            "voxel value {}, not a finite non-negative number",
+            value
+        )));
+    }
+    let densities = values.iter().step_by(channels).copied().collect();
+    let temperatures = (channels == 2).then(|| values.iter().skip(1).step_by(2).copied().collect());
+
+    Ok(Grid {
+        size,
+        densities,
+        temperatures,
+    })
+}
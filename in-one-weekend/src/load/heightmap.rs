@@ -0,0 +1,49 @@
+use crate::load::Error;
+use crate::shape::Heightmap;
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Loads a grayscale PNG as heights in `[0, 1]`, 16-bit images keeping their
+/// full precision.
+///
+/// Color images use their first channel.
+pub fn load_heightmap(path: impl AsRef<Path>) -> Result<Heightmap, Error> {
+    let path = path.as_ref();
+    let png = |error| Error::Png(path.to_owned(), error);
+
+    let file = File::open(path).map_err(|error| Error::Io(path.to_owned(), error))?;
+    let mut decoder = png::Decoder::new(BufReader::new(file));
+    // Unpacks palettes and grays below 8 bits
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().map_err(png)?;
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(png)?;
+    let buffer = &buffer[..info.buffer_size()];
+
+    let (width, depth) = (info.width as usize, info.height as usize);
+    if width < 2 || depth < 2 {
+        let message = format!("heightmap of {}×{} pixels is too small", width, depth);
+        return Err(Error::Invalid(path.to_owned(), message));
+    }
+
+    let channels = info.color_type.samples();
+    let heights = match info.bit_depth {
+        png::BitDepth::Sixteen => buffer
+            .chunks_exact(2 * channels)
+            .map(|pixel| f64::from(u16::from_be_bytes([pixel[0], pixel[1]])) / f64::from(u16::MAX))
+            .collect(),
+        _ => buffer
+            .chunks_exact(channels)
+            .map(|pixel| f64::from(pixel[0]) / f64::from(u8::MAX))
+            .collect(),
+    };
+
+    Ok(Heightmap {
+        width,
+        depth,
+        heights,
+    })
+}
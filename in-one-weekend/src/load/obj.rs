@@ -0,0 +1,456 @@
+use crate::load::load_heightmap;
+use crate::load::Error;
+use crate::material::Dielectric;
+use crate::material::Lambertian;
+use crate::material::Material;
+use crate::material::Metal;
+use crate::shape::Face;
+use crate::shape::Heightmap;
+use crate::shape::Mesh;
+use crate::shape::Surface;
+use crate::shape::Tessellation;
+use crate::shape::Uv;
+use crate::Vec3;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Material description from a `.mtl` library.
+struct Mtl {
+    /// `Kd`
+    diffuse: Vec3,
+    /// `Ks`
+    specular: Vec3,
+    /// `Ns`
+    shininess: f64,
+    /// `Ni`
+    index: Option<f64>,
+    /// `d`, or `1 - Tr`
+    opacity: f64,
+    /// `Tf`
+    transmission: Option<Vec3>,
+    /// `illum`
+    illumination: u32,
+    /// `disp`
+    displacement: Option<Displacement>,
+}
+
+/// Scalar texture pushing surfaces along their normals.
+struct Displacement {
+    heightmap: Heightmap,
+    /// Offset and scale of the `-mm` option.
+    base: f64,
+    gain: f64,
+}
+
+impl Displacement {
+    fn height(&self, uv: &Uv) -> f64 {
+        self.base + self.gain * self.heightmap.sample(uv)
+    }
+}
+
+impl Default for Mtl {
+    fn default() -> Mtl {
+        Mtl {
+            diffuse: Vec3::new(0.5, 0.5, 0.5),
+            specular: Vec3::zeros(),
+            shininess: 0.0,
+            index: None,
+            opacity: 1.0,
+            transmission: None,
+            illumination: 2,
+            displacement: None,
+        }
+    }
+}
+
+impl Mtl {
+    fn material(&self) -> Box<dyn Material> {
+        let transparent = self.opacity < 1.0 || matches!(self.illumination, 4 | 6 | 7 | 9);
+        if transparent {
+            let attenuation = self
+                .transmission
+                .unwrap_or_else(|| Vec3::new(1.0, 1.0, 1.0));
+            let index = self.index.filter(|&index| index > 1.0).unwrap_or(1.5);
+            return Dielectric::new(attenuation, index).boxed();
+        }
+
+        let reflective = self.illumination == 3 || self.specular.max() > self.diffuse.max();
+        if reflective {
+            // Phong exponent to roughness
+            let fuzz = (2.0 / (self.shininess.max(0.0) + 2.0)).sqrt();
+            return Metal::new(self.specular, fuzz.min(0.99)).boxed();
+        }
+
+        Lambertian::new(self.diffuse).boxed()
+    }
+}
+
+struct Triangle {
+    vertices: [usize; 3],
+    normals: Option<[usize; 3]>,
+    uvs: Option<[usize; 3]>,
+    material: Option<usize>,
+}
+
+#[derive(Default)]
+struct Group {
+    triangles: Vec<Triangle>,
+}
+
+/// Indices of one `f` statement corner, already resolved to 0-based.
+struct Corner {
+    vertex: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+/// Loads a Wavefront OBJ file and its MTL libraries.
+///
+/// Polygons are triangulated as fans and each group (`g` or `o`) becomes a
+/// separate `Mesh`. Groups with displacement maps are split as finely as
+/// `tessellation` asks before being displaced.
+pub fn load_obj(path: impl AsRef<Path>, tessellation: Tessellation) -> Result<Vec<Mesh>, Error> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|error| Error::Io(path.to_owned(), error))?;
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+
+    let mut library = HashMap::new();
+    let mut materials: Vec<Mtl> = Vec::new();
+    let mut material = None;
+
+    let mut names = HashMap::new();
+    let mut groups = vec![Group::default()];
+    let mut group = 0;
+
+    for (number, line) in content.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| Error::parse(path, number, message);
+
+        let line = line.split('#').next().unwrap_or_default();
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        match keyword {
+            "v" => {
+                // Optional `w` or vertex color components are ignored
+                let v = numbers(tokens, 3, 7).map_err(error)?;
+                positions.push(Vec3::new(v[0], v[1], v[2]));
+            }
+            "vn" => {
+                let n = numbers(tokens, 3, 3).map_err(error)?;
+                normals.push(Vec3::new(n[0], n[1], n[2]));
+            }
+            "vt" => {
+                let uv = numbers(tokens, 1, 3).map_err(error)?;
+                uvs.push(Uv::new(uv[0], uv.get(1).copied().unwrap_or(0.0)));
+            }
+            "f" => {
+                let counts = (positions.len(), uvs.len(), normals.len());
+                let corners = tokens
+                    .map(|token| corner(token, counts))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(error)?;
+
+                if corners.len() < 3 {
+                    return Err(error(format!("face with {} vertices", corners.len())));
+                }
+
+                let all_uvs = corners.iter().all(|corner| corner.uv.is_some());
+                let all_normals = corners.iter().all(|corner| corner.normal.is_some());
+
+                let first = &corners[0];
+                for pair in corners[1..].windows(2) {
+                    let triangle = [first, &pair[0], &pair[1]];
+                    groups[group].triangles.push(Triangle {
+                        vertices: triangle.map(|corner| corner.vertex),
+                        normals: all_normals.then(|| triangle.map(|c| c.normal.unwrap())),
+                        uvs: all_uvs.then(|| triangle.map(|c| c.uv.unwrap())),
+                        material,
+                    });
+                }
+            }
+            "g" | "o" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                group = *names.entry(name).or_insert_with(|| {
+                    groups.push(Group::default());
+                    groups.len() - 1
+                });
+            }
+            "usemtl" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                let index = library
+                    .get(&name)
+                    .ok_or_else(|| error(format!("unknown material `{}`", name)))?;
+                material = Some(*index);
+            }
+            "mtllib" => {
+                let directory = path.parent().unwrap_or_else(|| Path::new(""));
+                for file in tokens {
+                    for (name, mtl) in load_mtl(&directory.join(file))? {
+                        library.insert(name, materials.len());
+                        materials.push(mtl);
+                    }
+                }
+            }
+            // Smoothing groups, lines, points and free-form geometry
+            _ => {}
+        }
+    }
+
+    let meshes = groups
+        .into_iter()
+        .filter(|group| !group.triangles.is_empty())
+        .map(|group| compact(group, &positions, &normals, &uvs, &materials, tessellation))
+        .collect();
+
+    Ok(meshes)
+}
+
+/// Builds a `Mesh` holding only the data referenced by `group`.
+fn compact(
+    group: Group,
+    positions: &[Vec3],
+    normals: &[Vec3],
+    uvs: &[Uv],
+    mtls: &[Mtl],
+    tessellation: Tessellation,
+) -> Mesh {
+    fn remap<T: Copy>(
+        index: usize,
+        map: &mut HashMap<usize, usize>,
+        from: &[T],
+        to: &mut Vec<T>,
+    ) -> usize {
+        *map.entry(index).or_insert_with(|| {
+            to.push(from[index]);
+            to.len() - 1
+        })
+    }
+
+    let mut position_map = HashMap::new();
+    let mut normal_map = HashMap::new();
+    let mut uv_map = HashMap::new();
+    let mut material_map = HashMap::new();
+
+    let mut mesh_positions = Vec::new();
+    let mut mesh_normals = Vec::new();
+    let mut mesh_uvs = Vec::new();
+    let mut materials = Vec::new();
+
+    let faces = group
+        .triangles
+        .into_iter()
+        .map(|triangle| {
+            let vertices = triangle
+                .vertices
+                .map(|i| remap(i, &mut position_map, positions, &mut mesh_positions));
+            let normals = triangle.normals.map(|indices| {
+                indices.map(|i| remap(i, &mut normal_map, normals, &mut mesh_normals))
+            });
+            let uvs = triangle
+                .uvs
+                .map(|indices| indices.map(|i| remap(i, &mut uv_map, uvs, &mut mesh_uvs)));
+            let material = *material_map.entry(triangle.material).or_insert_with(|| {
+                let material = match triangle.material {
+                    Some(index) => mtls[index].material(),
+                    None => Mtl::default().material(),
+                };
+                materials.push(material);
+                materials.len() - 1
+            });
+
+            Face::new(vertices, normals, uvs, material)
+        })
+        .collect();
+
+    // Displacement maps by mesh material
+    let mut displacements = vec![None; materials.len()];
+    for (mtl, &material) in &material_map {
+        displacements[material] = mtl.and_then(|index| mtls[index].displacement.as_ref());
+    }
+    if displacements.iter().all(Option::is_none) {
+        return Mesh::new(mesh_positions, mesh_normals, mesh_uvs, faces, materials);
+    }
+
+    let mut surface = Surface {
+        positions: mesh_positions,
+        uvs: mesh_uvs,
+        faces,
+    };
+    surface.tessellate(tessellation);
+    let height = |material: usize, uv: Option<&Uv>| match (displacements[material], uv) {
+        (Some(displacement), Some(uv)) => displacement.height(uv),
+        _ => 0.0,
+    };
+    surface.displace(height, materials)
+}
+
+/// Parses a `v`, `v/vt`, `v//vn` or `v/vt/vn` face corner.
+fn corner(token: &str, (positions, uvs, normals): (usize, usize, usize)) -> Result<Corner, String> {
+    let mut indices = token.split('/');
+
+    let vertex = indices.next().unwrap_or_default();
+    let vertex = resolve(vertex, positions)?;
+
+    let uv = match indices.next() {
+        Some("") | None => None,
+        Some(uv) => Some(resolve(uv, uvs)?),
+    };
+
+    let normal = match indices.next() {
+        Some("") | None => None,
+        Some(normal) => Some(resolve(normal, normals)?),
+    };
+
+    if indices.next().is_some() {
+        return Err(format!("invalid face corner `{}`", token));
+    }
+
+    Ok(Corner { vertex, uv, normal })
+}
+
+/// Resolves a 1-based, possibly negative (relative), OBJ index.
+fn resolve(token: &str, count: usize) -> Result<usize, String> {
+    let index: isize = token
+        .parse()
+        .map_err(|_| format!("invalid index `{}`", token))?;
+
+    let resolved = match index {
+        0 => None,
+        index if index > 0 => Some(index as usize - 1),
+        index => count.checked_sub(index.unsigned_abs()),
+    };
+
+    resolved
+        .filter(|&index| index < count)
+        .ok_or_else(|| format!("index `{}` out of range", token))
+}
+
+fn numbers<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    min: usize,
+    max: usize,
+) -> Result<Vec<f64>, String> {
+    let numbers = tokens
+        .map(|token| {
+            token
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number `{}`", token))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if numbers.len() < min || max < numbers.len() {
+        return Err(format!(
+            "expected {} to {} numbers, found {}",
+            min,
+            max,
+            numbers.len()
+        ));
+    }
+
+    Ok(numbers)
+}
+
+fn load_mtl(path: &Path) -> Result<Vec<(String, Mtl)>, Error> {
+    let content = fs::read_to_string(path).map_err(|error| Error::Io(path.to_owned(), error))?;
+
+    let mut materials: Vec<(String, Mtl)> = Vec::new();
+
+    for (number, line) in content.lines().enumerate() {
+        let number = number + 1;
+        let error = |message: String| Error::parse(path, number, message);
+
+        let line = line.split('#').next().unwrap_or_default();
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        if keyword == "newmtl" {
+            let name = tokens.collect::<Vec<_>>().join(" ");
+            materials.push((name, Mtl::default()));
+            continue;
+        }
+
+        let mtl = match materials.last_mut() {
+            Some((_, mtl)) => mtl,
+            None if matches!(
+                keyword,
+                "Kd" | "Ks" | "Ns" | "Ni" | "d" | "Tr" | "Tf" | "illum"
+            ) =>
+            {
+                return Err(error(format!("`{}` before `newmtl`", keyword)))
+            }
+            None => continue,
+        };
+
+        let color = |tokens| -> Result<Vec3, Error> {
+            match numbers(tokens, 1, 3).map_err(error)?[..] {
+                [gray] => Ok(Vec3::new(gray, gray, gray)),
+                [r, g, b] => Ok(Vec3::new(r, g, b)),
+                _ => Err(error("expected 1 or 3 color components".to_owned())),
+            }
+        };
+        let scalar =
+            |tokens| -> Result<f64, Error> { Ok(numbers(tokens, 1, 1).map_err(error)?[0]) };
+
+        match keyword {
+            "Kd" => mtl.diffuse = color(tokens)?,
+            "Ks" => mtl.specular = color(tokens)?,
+            "Tf" => mtl.transmission = Some(color(tokens)?),
+            "Ns" => mtl.shininess = scalar(tokens)?,
+            "Ni" => mtl.index = Some(scalar(tokens)?),
+            "d" => mtl.opacity = scalar(tokens)?,
+            "Tr" => mtl.opacity = 1.0 - scalar(tokens)?,
+            "disp" => mtl.displacement = Some(displacement(path, number, tokens)?),
+            "illum" => {
+                let token = tokens.next().unwrap_or_default();
+                mtl.illumination = token
+                    .parse()
+                    .map_err(|_| error(format!("invalid illumination model `{}`", token)))?;
+            }
+            // Ambient, emissive and texture maps
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+/// Loads the PNG map of a `disp [-mm base gain] file` statement, relative to
+/// the library, other options being ignored.
+fn displacement<'a>(
+    library: &Path,
+    number: usize,
+    tokens: impl Iterator<Item = &'a str>,
+) -> Result<Displacement, Error> {
+    let error = |message: String| Error::parse(library, number, message);
+
+    let tokens: Vec<&str> = tokens.collect();
+    let (&file, options) = tokens
+        .split_last()
+        .ok_or_else(|| error("missing displacement map".to_owned()))?;
+
+    let (mut base, mut gain) = (0.0, 1.0);
+    if let Some(i) = options.iter().position(|&option| option == "-mm") {
+        let mm = numbers(options[i + 1..].iter().copied().take(2), 2, 2).map_err(error)?;
+        (base, gain) = (mm[0], mm[1]);
+    }
+
+    let directory = library.parent().unwrap_or_else(|| Path::new(""));
+    let heightmap = load_heightmap(directory.join(file))?;
+    Ok(Displacement {
+        heightmap,
+        base,
+        gain,
+    })
+}
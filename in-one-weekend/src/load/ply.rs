@@ -0,0 +1,488 @@
+use crate::hit::Hit;
+use crate::load::Error;
+use crate::material::Lambertian;
+use crate::material::Material;
+use crate::shape::Face;
+use crate::shape::Mesh;
+use crate::shape::Sphere;
+use crate::shape::Uv;
+use crate::texture::VertexColor;
+use crate::Vec3;
+
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Copy)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Option<Scalar> {
+        let scalar = match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return None,
+        };
+
+        Some(scalar)
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+
+    /// Value of full intensity when used as a color channel.
+    fn intensity(self) -> f64 {
+        match self {
+            Scalar::U8 => 255.0,
+            Scalar::U16 => 65_535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+enum Property {
+    Scalar(String, Scalar),
+    /// Name, length type and item type.
+    List(String, Scalar, Scalar),
+}
+
+impl Property {
+    fn name(&self) -> &str {
+        match self {
+            Property::Scalar(name, _) | Property::List(name, ..) => name,
+        }
+    }
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+impl Element {
+    fn position(&self, names: &[&str]) -> Option<usize> {
+        self.properties
+            .iter()
+            .position(|property| names.contains(&property.name()))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Encoding {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+enum Value {
+    Scalar(f64),
+    List(Vec<f64>),
+}
+
+impl Value {
+    fn scalar(&self) -> f64 {
+        match self {
+            Value::Scalar(value) => *value,
+            Value::List(values) => values.first().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Source of the element data following the header.
+trait Body {
+    fn read(&mut self, scalar: Scalar) -> Result<f64, String>;
+
+    /// Called after each element instance.
+    fn finish(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Line to report errors at.
+    fn line(&self) -> usize;
+}
+
+struct Ascii<'b> {
+    lines: std::iter::Enumerate<std::str::Lines<'b>>,
+    tokens: std::str::SplitWhitespace<'b>,
+    /// Number of header lines.
+    offset: usize,
+    line: usize,
+}
+
+impl Body for Ascii<'_> {
+    fn read(&mut self, _: Scalar) -> Result<f64, String> {
+        loop {
+            if let Some(token) = self.tokens.next() {
+                return token
+                    .parse()
+                    .map_err(|_| format!("invalid number `{}`", token));
+            }
+
+            let (number, line) = self.lines.next().ok_or("unexpected end of file")?;
+            self.line = self.offset + number + 1;
+            self.tokens = line.split_whitespace();
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        match self.tokens.next() {
+            Some(token) => Err(format!("unexpected value `{}`", token)),
+            None => Ok(()),
+        }
+    }
+
+    fn line(&self) -> usize {
+        self.line
+    }
+}
+
+struct Binary<'b> {
+    bytes: &'b [u8],
+    big_endian: bool,
+    /// Line of `end_header`.
+    line: usize,
+}
+
+impl Body for Binary<'_> {
+    fn read(&mut self, scalar: Scalar) -> Result<f64, String> {
+        let size = scalar.size();
+        if self.bytes.len() < size {
+            return Err("unexpected end of file".to_owned());
+        }
+
+        let (bytes, rest) = self.bytes.split_at(size);
+        self.bytes = rest;
+
+        let mut buffer = [0; 8];
+        buffer[..size].copy_from_slice(bytes);
+        if self.big_endian {
+            buffer[..size].reverse();
+        }
+
+        let value = match scalar {
+            Scalar::I8 => f64::from(buffer[0] as i8),
+            Scalar::U8 => f64::from(buffer[0]),
+            Scalar::I16 => f64::from(i16::from_le_bytes([buffer[0], buffer[1]])),
+            Scalar::U16 => f64::from(u16::from_le_bytes([buffer[0], buffer[1]])),
+            Scalar::I32 => f64::from(i32::from_le_bytes([
+                buffer[0], buffer[1], buffer[2], buffer[3],
+            ])),
+            Scalar::U32 => f64::from(u32::from_le_bytes([
+                buffer[0], buffer[1], buffer[2], buffer[3],
+            ])),
+            Scalar::F32 => f64::from(f32::from_le_bytes([
+                buffer[0], buffer[1], buffer[2], buffer[3],
+            ])),
+            Scalar::F64 => f64::from_le_bytes(buffer),
+        };
+
+        Ok(value)
+    }
+
+    fn line(&self) -> usize {
+        self.line
+    }
+}
+
+/// Loads a PLY file, ASCII or binary.
+///
+/// Files with faces give a `Mesh`, files without give a cloud of `Sphere`s
+/// sized after the point density. Per-vertex colors become `Lambertian`
+/// albedos, interpolated over the faces.
+pub fn load_ply(path: impl AsRef<Path>) -> Result<Vec<Box<dyn Hit>>, Error> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|error| Error::Io(path.to_owned(), error))?;
+
+    let (encoding, elements, lines, offset) = header(path, &bytes)?;
+    let data = &bytes[offset..];
+
+    let text;
+    let mut body: Box<dyn Body> = match encoding {
+        Encoding::Ascii => {
+            text = std::str::from_utf8(data)
+                .map_err(|_| Error::parse(path, lines + 1, "invalid ASCII body"))?;
+            Box::new(Ascii {
+                lines: text.lines().enumerate(),
+                tokens: "".split_whitespace(),
+                offset: lines,
+                line: lines,
+            })
+        }
+        Encoding::LittleEndian | Encoding::BigEndian => Box::new(Binary {
+            bytes: data,
+            big_endian: matches!(encoding, Encoding::BigEndian),
+            line: lines,
+        }),
+    };
+
+    let mut vertices = Vec::new();
+    let mut polygons = Vec::new();
+
+    for element in &elements {
+        // Not preallocated, the count being untrusted
+        let mut instances = Vec::new();
+        for index in 0..element.count {
+            let instance = element
+                .properties
+                .iter()
+                .map(|property| match property {
+                    Property::Scalar(_, scalar) => body.read(*scalar).map(Value::Scalar),
+                    Property::List(_, length, item) => {
+                        let length = body.read(*length)? as usize;
+                        let items = (0..length)
+                            .map(|_| body.read(*item))
+                            .collect::<Result<_, _>>()?;
+                        Ok(Value::List(items))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .and_then(|instance| body.finish().map(|_| instance))
+                .map_err(|message| {
+                    let message = format!("{} {}: {}", element.name, index, message);
+                    Error::parse(path, body.line(), message)
+                })?;
+
+            instances.push(instance);
+        }
+
+        match element.name.as_str() {
+            "vertex" => vertices = instances,
+            "face" => polygons = instances,
+            _ => {}
+        }
+    }
+
+    let vertex = elements.iter().find(|element| element.name == "vertex");
+    let vertex = match vertex {
+        Some(vertex) => vertex,
+        None => return Err(Error::parse(path, lines, "missing `vertex` element")),
+    };
+
+    let column = |names: &[&str]| vertex.position(names);
+    let coordinates = |names: [&[&str]; 3]| -> Option<Vec<Vec3>> {
+        let [x, y, z] = [column(names[0])?, column(names[1])?, column(names[2])?];
+        let vectors = vertices
+            .iter()
+            .map(|v| Vec3::new(v[x].scalar(), v[y].scalar(), v[z].scalar()))
+            .collect();
+        Some(vectors)
+    };
+
+    let positions = coordinates([&["x"], &["y"], &["z"]])
+        .ok_or_else(|| Error::parse(path, lines, "missing vertex `x`, `y` or `z`"))?;
+    let normals = coordinates([&["nx"], &["ny"], &["nz"]]);
+    let colors = coordinates([
+        &["red", "diffuse_red", "r"],
+        &["green", "diffuse_green", "g"],
+        &["blue", "diffuse_blue", "b"],
+    ])
+    .map(|colors| {
+        let red = column(&["red", "diffuse_red", "r"]).unwrap();
+        let intensity = match &vertex.properties[red] {
+            Property::Scalar(_, scalar) => scalar.intensity(),
+            Property::List(..) => 1.0,
+        };
+        colors
+            .into_iter()
+            .map(|color| color / intensity)
+            .collect::<Vec<_>>()
+    });
+    let uvs = match (
+        column(&["s", "u", "texture_u"]),
+        column(&["t", "v", "texture_v"]),
+    ) {
+        (Some(u), Some(v)) => Some(
+            vertices
+                .iter()
+                .map(|vertex| Uv::new(vertex[u].scalar(), vertex[v].scalar()))
+                .collect::<Vec<_>>(),
+        ),
+        _ => None,
+    };
+
+    let face = elements.iter().find(|element| element.name == "face");
+    let indices = face.and_then(|face| face.position(&["vertex_indices", "vertex_index"]));
+
+    let indices = match indices {
+        Some(indices) => indices,
+        None => return Ok(cloud(positions, colors)),
+    };
+
+    let mut faces = Vec::new();
+
+    for (index, polygon) in polygons.iter().enumerate() {
+        let invalid = || {
+            let message = format!("face {}: invalid vertex indices", index);
+            Error::parse(path, lines, message)
+        };
+
+        let polygon = match &polygon[indices] {
+            Value::List(polygon) => polygon,
+            Value::Scalar(_) => return Err(invalid()),
+        };
+        let valid = |&i: &f64| 0.0 <= i && i.fract() == 0.0 && i < positions.len() as f64;
+        if polygon.len() < 3 || !polygon.iter().all(valid) {
+            return Err(invalid());
+        }
+        let polygon: Vec<usize> = polygon.iter().map(|&i| i as usize).collect();
+
+        for pair in polygon[1..].windows(2) {
+            let triangle = [polygon[0], pair[0], pair[1]];
+            let face = Face::new(
+                triangle,
+                normals.is_some().then_some(triangle),
+                uvs.is_some().then_some(triangle),
+                0,
+            );
+            faces.push(face);
+        }
+    }
+
+    let gray = Vec3::new(0.5, 0.5, 0.5);
+    let material = Lambertian::new(VertexColor::new(gray)).boxed();
+    let mesh = Mesh::new(
+        positions,
+        normals.unwrap_or_default(),
+        uvs.unwrap_or_default(),
+        faces,
+        vec![material],
+    );
+    let mesh = match colors {
+        Some(colors) => mesh.with_colors(colors),
+        None => mesh,
+    };
+
+    Ok(vec![mesh.boxed()])
+}
+
+/// One `Sphere` per point.
+///
+/// The radius assumes points sampled over a surface, spaced by roughly the
+/// bounding box diagonal over the square root of their count.
+fn cloud(positions: Vec<Vec3>, colors: Option<Vec<Vec3>>) -> Vec<Box<dyn Hit>> {
+    if positions.is_empty() {
+        return Vec::new();
+    }
+
+    let min = positions.iter().fold(positions[0], |min, p| min.inf(p));
+    let max = positions.iter().fold(positions[0], |max, p| max.sup(p));
+    let spacing = (max - min).norm() / (positions.len() as f64).sqrt();
+    let radius = 0.5 * spacing.max(f64::EPSILON);
+
+    positions
+        .into_iter()
+        .enumerate()
+        .map(|(index, center)| {
+            let color = colors
+                .as_ref()
+                .map_or_else(|| Vec3::new(0.5, 0.5, 0.5), |colors| colors[index]);
+            Sphere::new(center, radius, Lambertian::new(color).boxed()).boxed()
+        })
+        .collect()
+}
+
+/// Parses the header, returning the encoding, the elements, the number of
+/// header lines and the offset of the body.
+fn header(path: &Path, bytes: &[u8]) -> Result<(Encoding, Vec<Element>, usize, usize), Error> {
+    let mut encoding = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut offset = 0;
+    let mut number = 0;
+
+    loop {
+        number += 1;
+        let error = |message: String| Error::parse(path, number, message);
+
+        let end = bytes[offset..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or_else(|| error("missing `end_header`".to_owned()))?;
+        let line = std::str::from_utf8(&bytes[offset..offset + end])
+            .map_err(|_| error("invalid header".to_owned()))?;
+        offset += end + 1;
+
+        let tokens: Vec<_> = line.split_whitespace().collect();
+
+        match tokens[..] {
+            ["ply"] if number == 1 => {}
+            _ if number == 1 => return Err(error("missing `ply` magic number".to_owned())),
+            ["format", format, _] => {
+                let format = match format {
+                    "ascii" => Encoding::Ascii,
+                    "binary_little_endian" => Encoding::LittleEndian,
+                    "binary_big_endian" => Encoding::BigEndian,
+                    _ => return Err(error(format!("unknown format `{}`", format))),
+                };
+                encoding = Some(format);
+            }
+            ["element", name, count] => {
+                let count = count
+                    .parse()
+                    .map_err(|_| error(format!("invalid element count `{}`", count)))?;
+                elements.push(Element {
+                    name: name.to_owned(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            ["property", "list", length, item, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| error("`property` before `element`".to_owned()))?;
+                let length = Scalar::parse(length)
+                    .ok_or_else(|| error(format!("unknown type `{}`", length)))?;
+                let item =
+                    Scalar::parse(item).ok_or_else(|| error(format!("unknown type `{}`", item)))?;
+                element
+                    .properties
+                    .push(Property::List(name.to_owned(), length, item));
+            }
+            ["property", scalar, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| error("`property` before `element`".to_owned()))?;
+                let scalar = Scalar::parse(scalar)
+                    .ok_or_else(|| error(format!("unknown type `{}`", scalar)))?;
+                element
+                    .properties
+                    .push(Property::Scalar(name.to_owned(), scalar));
+            }
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            ["end_header"] => break,
+            _ => return Err(error(format!("invalid header line `{}`", line))),
+        }
+    }
+
+    let encoding = encoding.ok_or_else(|| Error::parse(path, number, "missing `format`"))?;
+    // Instances reading nothing would not run out of data
+    let empty = elements
+        .iter()
+        .find(|element| element.count > 0 && element.properties.is_empty());
+    if let Some(element) = empty {
+        let message = format!("element `{}` without properties", element.name);
+        return Err(Error::parse(path, number, message));
+    }
+
+    Ok((encoding, elements, number, offset))
+}
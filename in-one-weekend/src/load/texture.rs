@@ -0,0 +1,59 @@
+use crate::load::Error;
+use crate::texture;
+use crate::texture::Bitmap;
+use crate::Vec3;
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Loads a PNG image as a texture, decoding sRGB values for color maps.
+///
+/// Gray images give gray colors and alpha channels are ignored.
+pub fn load_texture(path: impl AsRef<Path>, srgb: bool) -> Result<Bitmap, Error> {
+    let path = path.as_ref();
+    let png = |error| Error::Png(path.to_owned(), error);
+
+    let file = File::open(path).map_err(|error| Error::Io(path.to_owned(), error))?;
+    let mut decoder = png::Decoder::new(BufReader::new(file));
+    // Unpacks palettes and grays below 8 bits
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().map_err(png)?;
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(png)?;
+    let buffer = &buffer[..info.buffer_size()];
+
+    let values: Vec<f64> = match info.bit_depth {
+        png::BitDepth::Sixteen => buffer
+            .chunks_exact(2)
+            .map(|value| f64::from(u16::from_be_bytes([value[0], value[1]])) / f64::from(u16::MAX))
+            .collect(),
+        _ => buffer
+            .iter()
+            .map(|&value| f64::from(value) / f64::from(u8::MAX))
+            .collect(),
+    };
+
+    let decode = |value: f64| {
+        if srgb {
+            texture::srgb_to_linear(value)
+        } else {
+            value
+        }
+    };
+    let texels = values
+        .chunks_exact(info.color_type.samples())
+        .map(|pixel| match pixel {
+            [gray] | [gray, _] => Vec3::repeat(decode(*gray)),
+            [r, g, b, ..] => Vec3::new(decode(*r), decode(*g), decode(*b)),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    Ok(Bitmap::new(
+        info.width as usize,
+        info.height as usize,
+        texels,
+    ))
+}
@@ -1,22 +1,43 @@
 use clap::Parser;
 use nalgebra as na;
 
+mod aabb;
+mod atmosphere;
+mod bvh;
 mod camera;
 mod hit;
 mod image;
+mod load;
 mod material;
+mod polynomial;
 mod ray;
 mod scene;
 mod shape;
+mod texture;
 
+use crate::atmosphere::Atmosphere;
 use crate::camera::Camera;
+use crate::hit::Hit;
 use crate::image::Format;
 use crate::image::Image;
 use crate::image::Resolution;
+use crate::material::Phase;
+use crate::scene::Preset;
 use crate::scene::Scene;
+use crate::scene::CORNELL;
+use crate::shape::Tessellation;
+
+use std::path::PathBuf;
+use std::process;
 
 type Vec3 = na::Vector3<f64>;
 
+/// Height of the scenes of marbles and models, up to which the haze fills
+/// them by default.
+const HEIGHT: f64 = 10.0;
+/// Times the fog thins out by e over the scene height by default.
+const FOG: f64 = 5.0;
+
 #[derive(Parser)]
 struct Cli {
     #[clap(
@@ -35,11 +56,84 @@ struct Cli {
     )]
     resolution: Resolution,
 
+    #[clap(long, help = "sets the built-in scene", default_value = "random")]
+    scene: Preset,
+
     #[clap(short, long, help = "sets the numbers of rays per image pixel")]
     sampling: u32,
 
+    #[clap(
+        long,
+        help = "sets how long the shutter stays open, moving objects blurring over it and stopping after 1",
+        default_value = "1"
+    )]
+    shutter: f64,
+
     #[clap(short, long, help = "sets the numbers of threads", default_value = "0")]
     threads: usize,
+
+    #[clap(
+        short,
+        long,
+        help = "renders an OBJ, PLY or glTF model instead of the random scene"
+    )]
+    model: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "splits every edge of displaced OBJ meshes in two that many times",
+        conflicts_with = "edge"
+    )]
+    tessellate: Option<usize>,
+
+    #[clap(
+        long,
+        help = "splits the edges of displaced OBJ meshes down to that length"
+    )]
+    edge: Option<f64>,
+
+    #[clap(
+        long,
+        help = "replaces the flat ground of the random scene by a PNG heightmap"
+    )]
+    terrain: Option<PathBuf>,
+
+    #[clap(long, help = "covers the ground of the random scene with a PNG image")]
+    ground: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "adds a voxel grid of smoke or fire behind the random scene"
+    )]
+    volume: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "fills the scene with a uniform haze of that density, up to the ceiling",
+        conflicts_with = "fog"
+    )]
+    haze: Option<f64>,
+
+    #[clap(
+        long,
+        help = "fills the scene with a fog of that density on the ground, thinning out with height"
+    )]
+    fog: Option<f64>,
+
+    #[clap(
+        long,
+        help = "sets the height of the haze, 10 or the Cornell box height by default"
+    )]
+    ceiling: Option<f64>,
+
+    #[clap(
+        long,
+        help = "sets how fast the fog thins out, by e every 1 / falloff units, 5 times over the scene height by default"
+    )]
+    falloff: Option<f64>,
+
+    #[clap(long, help = "prints the acceleration structure statistics")]
+    stats: bool,
 }
 
 fn main() {
@@ -66,9 +160,92 @@ fn main() {
         image.aspect(),
         aperture,
         focus,
-    );
-    let scene = Scene::random();
+    )
+    .with_shutter(0.0, cli.shutter);
+
+    match &cli.model {
+        Some(path) => {
+            let tessellation = match (cli.tessellate, cli.edge) {
+                (_, Some(length)) if !(length.is_finite() && length > 0.0) => {
+                    eprintln!("error: invalid edge length `{}`", length);
+                    process::exit(1);
+                }
+                (_, Some(length)) => Tessellation::EdgeLength(length),
+                (level, None) => Tessellation::Level(level.unwrap_or(0)),
+            };
+            let (scene, loaded) =
+                load::load(path, image.aspect(), tessellation).unwrap_or_else(|error| {
+                    eprintln!("error: {}", error);
+                    process::exit(1);
+                });
+            let camera = loaded.map_or(camera, |loaded| loaded.with_shutter(0.0, cli.shutter));
+            render(&mut image, scene, &camera, &cli, HEIGHT);
+        }
+        None => match cli.scene {
+            Preset::Random => {
+                let terrain = cli.terrain.as_ref().map(|path| {
+                    load::load_heightmap(path).unwrap_or_else(|error| {
+                        eprintln!("error: {}", error);
+                        process::exit(1);
+                    })
+                });
+                let volume = cli.volume.as_ref().map(|path| {
+                    load::load_grid(path).unwrap_or_else(|error| {
+                        eprintln!("error: {}", error);
+                        process::exit(1);
+                    })
+                });
+                let texture = cli.ground.as_ref().map(|path| {
+                    load::load_texture(path, true).unwrap_or_else(|error| {
+                        eprintln!("error: {}", error);
+                        process::exit(1);
+                    })
+                });
+                let scene = Scene::random(terrain, texture, volume);
+                render(&mut image, scene, &camera, &cli, HEIGHT);
+            }
+            Preset::Crowd => render(&mut image, Scene::crowd(), &camera, &cli, HEIGHT),
+            Preset::Shapes => render(&mut image, Scene::shapes(), &camera, &cli, HEIGHT),
+            Preset::Sdf => render(&mut image, Scene::sdf(), &camera, &cli, HEIGHT),
+            Preset::Hair => render(&mut image, Scene::hair(), &camera, &cli, HEIGHT),
+            Preset::Motion => render(&mut image, Scene::motion(), &camera, &cli, HEIGHT),
+            Preset::Noise => render(&mut image, Scene::noise(), &camera, &cli, HEIGHT),
+            Preset::Cornell | Preset::Smoke => {
+                let origin = Vec3::new(278.0, 278.0, -800.0);
+                let look_at = Vec3::new(278.0, 278.0, 0.0);
+                let focus = (look_at - origin).norm();
+                let camera =
+                    Camera::new(origin, look_at, vertical, 40.0, image.aspect(), 0.0, focus)
+                        .with_shutter(0.0, cli.shutter);
+                let scene = match cli.scene {
+                    Preset::Smoke => Scene::smoke(),
+                    _ => Scene::cornell(),
+                };
+                render(&mut image, scene, &camera, &cli, CORNELL);
+            }
+        },
+    }
 
-    image.par_render(&scene, &camera);
     image.save_as(cli.format).unwrap();
 }
+
+/// Renders `scene`, its atmosphere scaled to its `height` unless set.
+fn render<T>(image: &mut Image, scene: Scene<T>, camera: &Camera, cli: &Cli, height: f64)
+where
+    T: Hit + Sync,
+{
+    if cli.stats {
+        eprintln!("{}", scene.stats());
+    }
+
+    let phase = || Phase::henyey_greenstein(Vec3::new(0.9, 0.9, 0.9), 0.5);
+    let ceiling = cli.ceiling.unwrap_or(height);
+    let falloff = cli.falloff.unwrap_or(FOG / height);
+    let scene = match (cli.haze, cli.fog) {
+        (Some(density), _) => scene.with_atmosphere(Atmosphere::haze(density, ceiling, phase())),
+        (None, Some(density)) => scene.with_atmosphere(Atmosphere::fog(density, falloff, phase())),
+        (None, None) => scene,
+    };
+
+    image.par_render(&scene, camera);
+}
@@ -5,12 +5,18 @@ use crate::ray::Ray;
 use crate::Vec3;
 
 mod dielectric;
+mod hair;
 mod lambertian;
+mod light;
 mod metal;
+mod phase;
 
 pub use crate::material::dielectric::*;
+pub use crate::material::hair::*;
 pub use crate::material::lambertian::*;
+pub use crate::material::light::*;
 pub use crate::material::metal::*;
+pub use crate::material::phase::*;
 
 pub trait Material: Send + Sync {
     fn boxed(self) -> Box<dyn Material>
@@ -21,6 +27,10 @@ pub trait Material: Send + Sync {
     }
 
     fn scatter(&self, ray: Ray, impact: &hit::Impact<'_>) -> Option<Scattered>;
+
+    fn emitted(&self, _impact: &hit::Impact<'_>) -> Vec3 {
+        Vec3::zeros()
+    }
 }
 
 #[derive(new)]
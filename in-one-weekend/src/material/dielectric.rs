@@ -1,4 +1,3 @@
-use derive_new::new;
 use rand::Rng;
 
 use crate::hit;
@@ -6,17 +5,27 @@ use crate::material;
 use crate::material::Material;
 use crate::material::Scattered;
 use crate::ray::Ray;
+use crate::texture::Texture;
 use crate::Vec3;
 
-#[derive(new)]
 pub struct Dielectric {
-    attenuation: Vec3,
+    attenuation: Box<dyn Texture>,
     /// Index of refraction.
-    index: f64,
+    index: Box<dyn Texture>,
+}
+
+impl Dielectric {
+    pub fn new(attenuation: impl Texture + 'static, index: impl Texture + 'static) -> Dielectric {
+        Dielectric {
+            attenuation: attenuation.boxed(),
+            index: index.boxed(),
+        }
+    }
 }
 
 impl Material for Dielectric {
     fn scatter(&self, ray: Ray, impact: &hit::Impact<'_>) -> Option<Scattered> {
+        let index = self.index.scalar(impact);
         let normal;
         let ratio;
         let cosine;
@@ -24,19 +33,28 @@ impl Material for Dielectric {
         let dot = ray.direction.dot(&impact.normal);
         if dot.is_sign_negative() {
             normal = impact.normal;
-            ratio = self.index.recip();
+            ratio = index.recip();
             cosine = -dot;
         } else {
             normal = -impact.normal;
-            ratio = self.index;
+            ratio = index;
             cosine = dot;
         }
 
-        let direction = refract(&ray.direction, &normal, ratio)
-            .filter(|_| !rand::thread_rng().gen_bool(schlick(cosine, self.index)))
-            .unwrap_or_else(|| material::reflect(&ray.direction, &normal));
-        let ray = ray.next(impact.point, direction);
-        Some(Scattered::new(ray, self.attenuation))
+        let refracted = refract(&ray.direction, &normal, ratio)
+            .filter(|_| !rand::thread_rng().gen_bool(schlick(cosine, index)));
+        let ray = match refracted {
+            Some(direction) => ray.specular(impact, &normal, direction, |direction, normal| {
+                refract(&direction.normalize(), normal, ratio)
+            }),
+            None => {
+                let direction = material::reflect(&ray.direction, &normal);
+                ray.specular(impact, &normal, direction, |direction, normal| {
+                    Some(material::reflect(direction, normal))
+                })
+            }
+        };
+        Some(Scattered::new(ray, self.attenuation.value(impact)))
     }
 }
 
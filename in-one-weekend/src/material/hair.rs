@@ -0,0 +1,318 @@
+use crate::hit;
+use crate::material::Material;
+use crate::material::Scattered;
+use crate::ray::Ray;
+use crate::Vec3;
+
+use std::f64::consts::LN_2;
+use std::f64::consts::PI;
+use std::f64::consts::TAU;
+
+/// Scattering lobes modeled explicitly, the remaining ones being summed up.
+const P_MAX: usize = 3;
+/// Index of refraction of keratin.
+const ETA: f64 = 1.55;
+/// Tilt of the cuticle scales, in degrees.
+const ALPHA: f64 = 2.0;
+
+/// Absorption coefficients of eumelanin and pheomelanin.
+const EUMELANIN: [f64; 3] = [0.419, 0.697, 1.37];
+const PHEOMELANIN: [f64; 3] = [0.187, 0.4, 1.05];
+
+/// Fiber scattering model of d'Eon et al. and Chiang et al., splitting light
+/// into reflection (R), transmission (TT), internal reflection (TRT) and
+/// higher order lobes.
+///
+/// Needs impacts with a tangent along the fiber. Its parameters are the same
+/// along all fibers, the lobes being precomputed from them.
+pub struct Hair {
+    /// Absorption inside the fiber, per unit of diameter.
+    absorption: Vec3,
+    /// Longitudinal variance of each lobe.
+    variances: [f64; P_MAX + 1],
+    /// Azimuthal logistic scale.
+    scale: f64,
+    /// Sines and cosines of 2^k α, tilting the lobes.
+    tilts: [(f64, f64); 3],
+}
+
+impl Hair {
+    /// `longitudinal` and `azimuthal` are roughnesses in `[0, 1]`.
+    pub fn new(absorption: Vec3, longitudinal: f64, azimuthal: f64) -> Hair {
+        let beta_m = longitudinal;
+        let v = (0.726 * beta_m + 0.812 * beta_m.powi(2) + 3.7 * beta_m.powi(20)).powi(2);
+        let variances = [v, 0.25 * v, 4.0 * v, 4.0 * v];
+
+        let beta_n = azimuthal;
+        let scale =
+            (PI / 8.0).sqrt() * (0.265 * beta_n + 1.194 * beta_n.powi(2) + 5.372 * beta_n.powi(22));
+
+        let mut tilts = [(ALPHA.to_radians().sin(), 0.0); 3];
+        tilts[0].1 = (1.0 - tilts[0].0.powi(2)).max(0.0).sqrt();
+        for i in 1..3 {
+            let (sin, cos) = tilts[i - 1];
+            tilts[i] = (2.0 * cos * sin, cos.powi(2) - sin.powi(2));
+        }
+
+        Hair {
+            absorption,
+            variances,
+            scale,
+            tilts,
+        }
+    }
+
+    /// Natural hair colored by its concentrations of eumelanin (black to
+    /// brown) and pheomelanin (red).
+    pub fn melanin(eumelanin: f64, pheomelanin: f64, longitudinal: f64, azimuthal: f64) -> Hair {
+        let absorption = eumelanin * Vec3::from(EUMELANIN) + pheomelanin * Vec3::from(PHEOMELANIN);
+        Hair::new(absorption, longitudinal, azimuthal)
+    }
+
+    /// Fiber whose multiple scattering roughly shows `color`.
+    pub fn colored(color: Vec3, longitudinal: f64, azimuthal: f64) -> Hair {
+        let b = azimuthal;
+        let denominator = 5.969 - 0.215 * b + 2.532 * b.powi(2) - 10.73 * b.powi(3)
+            + 5.574 * b.powi(4)
+            + 0.245 * b.powi(5);
+        let absorption = color.map(|c| (c.max(1e-4).ln() / denominator).powi(2));
+        Hair::new(absorption, longitudinal, azimuthal)
+    }
+
+    /// Longitudinal angles of `direction`, tilted for lobe `p`.
+    fn tilted(&self, p: usize, sin: f64, cos: f64) -> (f64, f64) {
+        let (tilted_sin, tilted_cos) = match p {
+            0 => (
+                sin * self.tilts[1].1 - cos * self.tilts[1].0,
+                cos * self.tilts[1].1 + sin * self.tilts[1].0,
+            ),
+            1 => (
+                sin * self.tilts[0].1 + cos * self.tilts[0].0,
+                cos * self.tilts[0].1 - sin * self.tilts[0].0,
+            ),
+            2 => (
+                sin * self.tilts[2].1 + cos * self.tilts[2].0,
+                cos * self.tilts[2].1 - sin * self.tilts[2].0,
+            ),
+            _ => (sin, cos),
+        };
+
+        (tilted_sin, tilted_cos.abs())
+    }
+}
+
+/// Geometry of a path through the fiber, from the outgoing direction.
+struct Fiber {
+    sin_o: f64,
+    cos_o: f64,
+    phi_o: f64,
+    gamma_o: f64,
+    gamma_t: f64,
+    /// Attenuation of each lobe.
+    lobes: [Vec3; P_MAX + 1],
+}
+
+impl Fiber {
+    /// `outgoing` is in the fiber frame, x along the fiber and z the
+    /// normal, the offset across the fiber being `h`.
+    fn new(hair: &Hair, outgoing: &Vec3, h: f64) -> Fiber {
+        let sin_o = outgoing.x;
+        let cos_o = (1.0 - sin_o.powi(2)).max(0.0).sqrt();
+        let phi_o = outgoing.y.atan2(outgoing.z);
+        let gamma_o = h.asin();
+
+        // Refracted ray inside the fiber
+        let sin_t = sin_o / ETA;
+        let cos_t = (1.0 - sin_t.powi(2)).max(0.0).sqrt();
+        let eta = (ETA.powi(2) - sin_o.powi(2)).sqrt() / cos_o.max(1e-8);
+        let sin_gamma_t = (h / eta).clamp(-1.0, 1.0);
+        let cos_gamma_t = (1.0 - sin_gamma_t.powi(2)).max(0.0).sqrt();
+        let gamma_t = sin_gamma_t.asin();
+
+        let transmittance = (-hair.absorption * (2.0 * cos_gamma_t / cos_t)).map(f64::exp);
+
+        let cos_gamma_o = (1.0 - h.powi(2)).max(0.0).sqrt();
+        let f = fresnel(cos_o * cos_gamma_o, ETA);
+        let mut lobes = [Vec3::zeros(); P_MAX + 1];
+        lobes[0] = Vec3::repeat(f);
+        lobes[1] = (1.0 - f).powi(2) * transmittance;
+        for p in 2..P_MAX {
+            lobes[p] = f * lobes[p - 1].component_mul(&transmittance);
+        }
+        let residual = (Vec3::repeat(1.0) - f * transmittance).map(|x| x.max(1e-8));
+        lobes[P_MAX] =
+            (f * lobes[P_MAX - 1].component_mul(&transmittance)).component_div(&residual);
+
+        Fiber {
+            sin_o,
+            cos_o,
+            phi_o,
+            gamma_o,
+            gamma_t,
+            lobes,
+        }
+    }
+
+    /// Probability of sampling each lobe.
+    fn weights(&self) -> [f64; P_MAX + 1] {
+        let luminance = self.lobes.map(|lobe| lobe.mean());
+        let total: f64 = luminance.iter().sum();
+        luminance.map(|l| if total > 0.0 { l / total } else { 0.0 })
+    }
+
+    /// Azimuthal offset of lobe `p`.
+    fn phi(&self, p: usize) -> f64 {
+        2.0 * p as f64 * self.gamma_t - 2.0 * self.gamma_o + p as f64 * PI
+    }
+
+    /// Scattering function and sampling density towards `incident`, without
+    /// the cosine factor.
+    fn evaluate(&self, hair: &Hair, incident: &Vec3) -> (Vec3, f64) {
+        let sin_i = incident.x;
+        let cos_i = (1.0 - sin_i.powi(2)).max(0.0).sqrt();
+        let phi = incident.y.atan2(incident.z) - self.phi_o;
+
+        let weights = self.weights();
+        let mut value = Vec3::zeros();
+        let mut density = 0.0;
+        for (p, (lobe, weight)) in self.lobes.iter().zip(weights).enumerate().take(P_MAX) {
+            let (sin_o, cos_o) = hair.tilted(p, self.sin_o, self.cos_o);
+            let m = longitudinal(cos_i, cos_o, sin_i, sin_o, hair.variances[p]);
+            let n = azimuthal(phi - self.phi(p), hair.scale);
+            value += m * n * lobe;
+            density += m * n * weight;
+        }
+
+        let m = longitudinal(cos_i, self.cos_o, sin_i, self.sin_o, hair.variances[P_MAX]);
+        value += m * self.lobes[P_MAX] / TAU;
+        density += m * weights[P_MAX] / TAU;
+
+        (value, density)
+    }
+}
+
+impl Material for Hair {
+    fn scatter(&self, ray: Ray, impact: &hit::Impact<'_>) -> Option<Scattered> {
+        // Fiber frame: x along the fiber, z the normal facing the viewer
+        let normal = impact.facing(&ray.direction);
+        let x = impact
+            .tangent
+            .map(|tangent| tangent - tangent.dot(&normal) * normal)
+            .and_then(|tangent| tangent.try_normalize(1e-12))
+            .unwrap_or_else(|| {
+                normal
+                    .cross(&Vec3::y())
+                    .try_normalize(1e-12)
+                    .unwrap_or(Vec3::x())
+            });
+        let y = normal.cross(&x);
+        let to_local = |v: &Vec3| Vec3::new(v.dot(&x), v.dot(&y), v.dot(&normal));
+
+        let outgoing = to_local(&-ray.direction);
+        let h = (outgoing.y / outgoing.y.hypot(outgoing.z)).clamp(-1.0, 1.0);
+        let fiber = Fiber::new(self, &outgoing, if h.is_finite() { h } else { 0.0 });
+
+        // Picks a lobe, then its longitudinal and azimuthal angles
+        let weights = fiber.weights();
+        let mut pick = rand::random::<f64>();
+        let p = (0..P_MAX)
+            .find(|&p| {
+                if pick < weights[p] {
+                    return true;
+                }
+                pick -= weights[p];
+                false
+            })
+            .unwrap_or(P_MAX);
+
+        let (sin_o, cos_o) = self.tilted(p, fiber.sin_o, fiber.cos_o);
+        let v = self.variances[p];
+        let u = rand::random::<f64>().max(1e-5);
+        let cos_theta = 1.0 + v * (u + (1.0 - u) * (-2.0 / v).exp()).ln();
+        let sin_theta = (1.0 - cos_theta.powi(2)).max(0.0).sqrt();
+        let cos_phi = (TAU * rand::random::<f64>()).cos();
+        let sin_i = (-cos_theta * sin_o + sin_theta * cos_phi * cos_o).clamp(-1.0, 1.0);
+        let cos_i = (1.0 - sin_i.powi(2)).max(0.0).sqrt();
+
+        let u = rand::random::<f64>();
+        let delta = if p < P_MAX {
+            fiber.phi(p) + sample_azimuthal(u, self.scale)
+        } else {
+            TAU * u
+        };
+        let phi_i = fiber.phi_o + delta;
+
+        let incident = Vec3::new(sin_i, cos_i * phi_i.cos(), cos_i * phi_i.sin());
+        let (value, density) = fiber.evaluate(self, &incident);
+        if density <= 0.0 {
+            return None;
+        }
+
+        let direction = incident.x * x + incident.y * y + incident.z * normal;
+        let ray = ray.next(impact.point, direction);
+        Some(Scattered::new(ray, value / density))
+    }
+}
+
+/// Fresnel reflectance of a dielectric of index `eta` in the air.
+fn fresnel(cos_i: f64, eta: f64) -> f64 {
+    let cos_i = cos_i.clamp(-1.0, 1.0);
+    let sin_t = (1.0 - cos_i.powi(2)).max(0.0).sqrt() / eta;
+    if sin_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_t = (1.0 - sin_t.powi(2)).max(0.0).sqrt();
+    let parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    0.5 * (parallel.powi(2) + perpendicular.powi(2))
+}
+
+/// Modified Bessel function of the first kind, of order 0.
+fn bessel(x: f64) -> f64 {
+    let mut value = 0.0;
+    let mut term = 1.0;
+    for i in 0..10 {
+        value += term;
+        term *= (x / 2.0).powi(2) / f64::from(i + 1).powi(2);
+    }
+    value
+}
+
+fn log_bessel(x: f64) -> f64 {
+    if x > 12.0 {
+        x + 0.5 * (-(TAU).ln() + x.recip().ln() + (8.0 * x).recip())
+    } else {
+        bessel(x).ln()
+    }
+}
+
+/// Longitudinal scattering of variance `v`.
+fn longitudinal(cos_i: f64, cos_o: f64, sin_i: f64, sin_o: f64, v: f64) -> f64 {
+    let a = cos_i * cos_o / v;
+    let b = sin_i * sin_o / v;
+    if v <= 0.1 {
+        (log_bessel(a) - b - v.recip() + LN_2 + (2.0 * v).recip().ln()).exp()
+    } else {
+        (-b).exp() * bessel(a) / ((v.recip()).sinh() * 2.0 * v)
+    }
+}
+
+fn logistic_cdf(x: f64, scale: f64) -> f64 {
+    (1.0 + (-x / scale).exp()).recip()
+}
+
+/// Logistic distribution of `scale` trimmed to `[-π, π]`.
+fn azimuthal(phi: f64, scale: f64) -> f64 {
+    let phi = (phi + PI).rem_euclid(TAU) - PI;
+    let e = (-phi.abs() / scale).exp();
+    let logistic = e / (scale * (1.0 + e).powi(2));
+    logistic / (logistic_cdf(PI, scale) - logistic_cdf(-PI, scale))
+}
+
+fn sample_azimuthal(u: f64, scale: f64) -> f64 {
+    let low = logistic_cdf(-PI, scale);
+    let k = logistic_cdf(PI, scale) - low;
+    let x = -scale * ((u * k + low).recip() - 1.0).ln();
+    x.clamp(-PI, PI)
+}
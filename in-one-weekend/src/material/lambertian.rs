@@ -1,22 +1,27 @@
-use derive_new::new;
-
 use crate::hit;
 use crate::material::Material;
 use crate::material::Scattered;
 use crate::ray::Ray;
 use crate::shape;
-use crate::Vec3;
+use crate::texture::Texture;
 
-#[derive(new)]
 pub struct Lambertian {
-    albedo: Vec3,
+    albedo: Box<dyn Texture>,
+}
+
+impl Lambertian {
+    pub fn new(albedo: impl Texture + 'static) -> Lambertian {
+        Lambertian {
+            albedo: albedo.boxed(),
+        }
+    }
 }
 
 impl Material for Lambertian {
     fn scatter(&self, ray: Ray, impact: &hit::Impact<'_>) -> Option<Scattered> {
-        let direction = impact.normal + shape::random_in_unit_sphere();
+        let direction = impact.facing(&ray.direction) + shape::random_in_unit_sphere();
         let ray = ray.next(impact.point, direction);
 
-        Some(Scattered::new(ray, self.albedo))
+        Some(Scattered::new(ray, self.albedo.value(impact)))
     }
 }
@@ -0,0 +1,29 @@
+use crate::hit;
+use crate::material::Material;
+use crate::material::Scattered;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::Vec3;
+
+/// Diffuse emitter, not reflecting any light.
+pub struct Light {
+    emission: Box<dyn Texture>,
+}
+
+impl Light {
+    pub fn new(emission: impl Texture + 'static) -> Light {
+        Light {
+            emission: emission.boxed(),
+        }
+    }
+}
+
+impl Material for Light {
+    fn scatter(&self, _: Ray, _: &hit::Impact<'_>) -> Option<Scattered> {
+        None
+    }
+
+    fn emitted(&self, impact: &hit::Impact<'_>) -> Vec3 {
+        self.emission.value(impact)
+    }
+}
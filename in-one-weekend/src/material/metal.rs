@@ -3,32 +3,41 @@ use crate::material::Material;
 use crate::material::Scattered;
 use crate::ray::Ray;
 use crate::shape;
+use crate::texture::Texture;
 use crate::Vec3;
 
 pub struct Metal {
-    albedo: Vec3,
-    fuzz: f64,
+    albedo: Box<dyn Texture>,
+    /// Clamped below 1 where evaluated.
+    fuzz: Box<dyn Texture>,
 }
 
 impl Metal {
-    pub fn new(albedo: Vec3, fuzz: f64) -> Metal {
-        assert!((0.0..1.0).contains(&fuzz));
-        Metal { albedo, fuzz }
+    pub fn new(albedo: impl Texture + 'static, fuzz: impl Texture + 'static) -> Metal {
+        Metal {
+            albedo: albedo.boxed(),
+            fuzz: fuzz.boxed(),
+        }
     }
 }
 
 impl Material for Metal {
     fn scatter(&self, ray: Ray, impact: &hit::Impact<'_>) -> Option<Scattered> {
-        let reflected = reflect(&ray.direction, &impact.normal);
-        let fuzzed = reflected + self.fuzz * shape::random_in_unit_sphere();
+        let normal = impact.facing(&ray.direction);
+        let reflected = reflect(&ray.direction, &normal);
+        let fuzz = self.fuzz.scalar(impact).clamp(0.0, 0.99);
+        let fuzzed = reflected + fuzz * shape::random_in_unit_sphere();
 
-        if fuzzed.dot(&impact.normal).is_sign_negative() {
+        if fuzzed.dot(&normal).is_sign_negative() {
             return None;
         }
 
-        let ray = ray.next(impact.point, fuzzed);
+        // Differentials fuzzed alike
+        let ray = ray.specular(impact, &normal, fuzzed, |direction, normal| {
+            Some(reflect(direction, normal) + (fuzzed - reflected))
+        });
 
-        Some(Scattered::new(ray, self.albedo))
+        Some(Scattered::new(ray, self.albedo.value(impact)))
     }
 }
 
@@ -0,0 +1,67 @@
+use crate::hit;
+use crate::material::Material;
+use crate::material::Scattered;
+use crate::ray::Ray;
+use crate::shape;
+use crate::texture::Texture;
+
+use std::f64::consts::TAU;
+
+/// Phase function of a participating medium, scattering light within its
+/// volume rather than off a surface.
+///
+/// Henyey-Greenstein with asymmetry `g` in `(-1, 1)`: positive values favour
+/// forward scattering, as in fog, and negative ones back scattering. It is
+/// the same throughout the medium, unlike the albedo.
+pub struct Phase {
+    albedo: Box<dyn Texture>,
+    g: f64,
+}
+
+impl Phase {
+    /// Scatters equally in all directions, as in smoke.
+    pub fn isotropic(albedo: impl Texture + 'static) -> Phase {
+        Phase::henyey_greenstein(albedo, 0.0)
+    }
+
+    pub fn henyey_greenstein(albedo: impl Texture + 'static, g: f64) -> Phase {
+        assert!(g.abs() < 1.0);
+
+        Phase {
+            albedo: albedo.boxed(),
+            g,
+        }
+    }
+
+    /// Cosine between the propagation and scattered directions.
+    fn sample_cos(&self) -> f64 {
+        let u = rand::random::<f64>();
+        if self.g.abs() < 1e-3 {
+            return 1.0 - 2.0 * u;
+        }
+
+        let g = self.g;
+        let ratio = (1.0 - g.powi(2)) / (1.0 + g - 2.0 * g * u);
+        ((1.0 + g.powi(2) - ratio.powi(2)) / (2.0 * g)).clamp(-1.0, 1.0)
+    }
+}
+
+impl Material for Phase {
+    fn scatter(&self, ray: Ray, impact: &hit::Impact<'_>) -> Option<Scattered> {
+        let w = ray.direction.normalize();
+        let u = loop {
+            if let Some(u) = w.cross(&shape::random_on_unit_sphere()).try_normalize(1e-6) {
+                break u;
+            }
+        };
+        let v = w.cross(&u);
+
+        let cos = self.sample_cos();
+        let sin = (1.0 - cos.powi(2)).max(0.0).sqrt();
+        let phi = TAU * rand::random::<f64>();
+        let direction = cos * w + sin * (phi.cos() * u + phi.sin() * v);
+
+        let ray = ray.next(impact.point, direction);
+        Some(Scattered::new(ray, self.albedo.value(impact)))
+    }
+}
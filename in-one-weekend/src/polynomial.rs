@@ -0,0 +1,167 @@
+//! Real roots of low degree polynomials.
+//!
+//! Coefficients are given from the highest degree down, roots are returned in
+//! increasing order.
+
+/// Below this, a leading coefficient is taken as zero.
+const EPSILON: f64 = 1e-12;
+
+/// Value of the polynomial at `x`.
+pub fn evaluate(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().fold(0.0, |value, c| value * x + c)
+}
+
+/// Value of the derivative at `x`.
+fn derivative(coefficients: &[f64], x: f64) -> f64 {
+    let degree = coefficients.len().saturating_sub(1);
+    coefficients[..degree]
+        .iter()
+        .enumerate()
+        .fold(0.0, |value, (i, c)| value * x + (degree - i) as f64 * c)
+}
+
+/// Refines `root` with a few Newton iterations, the closed forms losing
+/// precision for clustered roots.
+fn polish(coefficients: &[f64], mut root: f64) -> f64 {
+    for _ in 0..4 {
+        let slope = derivative(coefficients, root);
+        if slope.abs() < f64::MIN_POSITIVE {
+            break;
+        }
+
+        let step = evaluate(coefficients, root) / slope;
+        if !step.is_finite() {
+            break;
+        }
+
+        root -= step;
+    }
+
+    root
+}
+
+fn sorted(mut roots: Vec<f64>) -> Vec<f64> {
+    roots.retain(|root| root.is_finite());
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Roots of `a x + b`.
+pub fn linear(a: f64, b: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return Vec::new();
+    }
+
+    vec![-b / a]
+}
+
+/// Roots of `a x² + b x + c`.
+pub fn quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return linear(b, c);
+    }
+
+    let discriminant = b.powi(2) - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+
+    // Avoids the cancellation of -b ± √Δ
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        return vec![0.0, 0.0];
+    }
+
+    sorted(vec![q / a, c / q])
+}
+
+/// Roots of `a x³ + b x² + c x + d`.
+pub fn cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return quadratic(b, c, d);
+    }
+
+    let (b, c, d) = (b / a, c / a, d / a);
+
+    // Depressed: t³ + p t + q with x = t - b / 3
+    let shift = b / 3.0;
+    let p = c - b * shift;
+    let q = 2.0 * shift.powi(3) - c * shift + d;
+
+    let roots = if p.abs() < EPSILON && q.abs() < EPSILON {
+        vec![0.0]
+    } else {
+        let discriminant = (q / 2.0).powi(2) + (p / 3.0).powi(3);
+        if discriminant > 0.0 {
+            // One real root (Cardano)
+            let sqrt = discriminant.sqrt();
+            vec![(-q / 2.0 + sqrt).cbrt() + (-q / 2.0 - sqrt).cbrt()]
+        } else {
+            // Three real roots (trigonometric)
+            let radius = 2.0 * (-p / 3.0).max(0.0).sqrt();
+            let cosine = if radius == 0.0 {
+                0.0
+            } else {
+                (3.0 * q / (p * radius)).clamp(-1.0, 1.0)
+            };
+            let angle = cosine.acos() / 3.0;
+            (0..3)
+                .map(|k| radius * (angle - std::f64::consts::TAU * f64::from(k) / 3.0).cos())
+                .collect()
+        }
+    };
+
+    let coefficients = [1.0, b, c, d];
+    sorted(
+        roots
+            .into_iter()
+            .map(|t| polish(&coefficients, t - shift))
+            .collect(),
+    )
+}
+
+/// Roots of `a x⁴ + b x³ + c x² + d x + e`, by Ferrari's method.
+pub fn quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return cubic(b, c, d, e);
+    }
+
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+
+    // Depressed: y⁴ + p y² + q y + r with x = y - b / 4
+    let shift = b / 4.0;
+    let p = c - 6.0 * shift.powi(2);
+    let q = d - 2.0 * c * shift + 8.0 * shift.powi(3);
+    let r = e - d * shift + c * shift.powi(2) - 3.0 * shift.powi(4);
+
+    let roots = if q.abs() < EPSILON {
+        // Biquadratic
+        quadratic(1.0, p, r)
+            .into_iter()
+            .filter(|z| *z >= 0.0)
+            .flat_map(|z| [-z.sqrt(), z.sqrt()])
+            .collect()
+    } else {
+        // The resolvent cubic has a positive root since q ≠ 0
+        let m = cubic(1.0, p, 0.25 * p.powi(2) - r, -0.125 * q.powi(2))
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max);
+        if m <= 0.0 {
+            return Vec::new();
+        }
+
+        let sqrt = (2.0 * m).sqrt();
+        let offset = q / (2.0 * sqrt);
+        let mut roots = quadratic(1.0, sqrt, 0.5 * p + m - offset);
+        roots.extend(quadratic(1.0, -sqrt, 0.5 * p + m + offset));
+        roots
+    };
+
+    let coefficients = [1.0, b, c, d, e];
+    sorted(
+        roots
+            .into_iter()
+            .map(|y| polish(&coefficients, y - shift))
+            .collect(),
+    )
+}
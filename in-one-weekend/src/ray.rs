@@ -1,3 +1,5 @@
+use crate::hit;
+use crate::na;
 use crate::Vec3;
 
 const MAX_DEPTH: usize = 64;
@@ -5,30 +7,102 @@ const MAX_DEPTH: usize = 64;
 pub struct Ray {
     pub origin: Vec3,
     pub direction: Vec3,
+    /// Instant within the shutter interval, moving shapes being tested there.
+    pub time: f64,
+    /// Rays through the next pixels along x and y, telling how much of the
+    /// scene a pixel covers.
+    pub differentials: Option<[Differential; 2]>,
     depth: usize,
 }
 
+/// Neighbouring ray, offset by a pixel.
+#[derive(Clone, Copy)]
+pub struct Differential {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
 impl Ray {
-    pub fn new(origin: Vec3, direction: Vec3) -> Ray {
+    pub fn new(origin: Vec3, direction: Vec3, time: f64) -> Ray {
         Ray {
             origin,
             direction: direction.normalize(),
+            time,
+            differentials: None,
             depth: 0,
         }
     }
 
+    pub fn with_differentials(mut self, differentials: [Differential; 2]) -> Self {
+        self.differentials = Some(differentials);
+        self
+    }
+
     pub fn is_active(&self) -> bool {
         self.depth < MAX_DEPTH
     }
 
+    /// Bounces diffusely, the differentials being lost.
     pub fn next(self, origin: Vec3, direction: Vec3) -> Ray {
         Ray {
             origin,
             direction: direction.normalize(),
+            time: self.time,
+            differentials: None,
             depth: self.depth + 1,
         }
     }
 
+    /// Bounces off `impact`, facing `normal`, towards `direction`, the
+    /// differentials following through `bend`, the same reflection or
+    /// refraction of any direction off any normal.
+    ///
+    /// The differentials are lost on surfaces of unknown curvature.
+    pub fn specular<F>(
+        self,
+        impact: &hit::Impact<'_>,
+        normal: &Vec3,
+        direction: Vec3,
+        bend: F,
+    ) -> Ray
+    where
+        F: Fn(&Vec3, &Vec3) -> Option<Vec3>,
+    {
+        let differentials = self.differentials.and_then(|rays| {
+            let (spread, curvature) = (impact.spread?, impact.curvature?);
+            // Turning the other way when facing the back
+            let curvature = normal.dot(&impact.normal).signum() * curvature;
+
+            let [x, y] = [0, 1].map(|i| {
+                let normal = (normal + curvature * spread[i]).normalize();
+                Some(Differential {
+                    origin: impact.point + spread[i],
+                    direction: bend(&rays[i].direction, &normal)?,
+                })
+            });
+            Some([x?, y?])
+        });
+
+        Ray {
+            differentials,
+            ..self.next(impact.point, direction)
+        }
+    }
+
+    /// Moves the ray by `transform`, keeping its parameterization: the
+    /// direction is not normalized.
+    ///
+    /// The differentials are dropped, shapes not needing them.
+    pub fn transform(&self, transform: &na::Affine3<f64>) -> Ray {
+        Ray {
+            origin: transform.transform_point(&self.origin.into()).coords,
+            direction: transform.transform_vector(&self.direction),
+            time: self.time,
+            differentials: None,
+            depth: self.depth,
+        }
+    }
+
     pub fn point_at(&self, t: f64) -> Vec3 {
         self.origin + t * self.direction
     }
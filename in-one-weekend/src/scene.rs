@@ -1,71 +1,276 @@
-use derive_new::new;
+use itertools::Either;
+use strum_macros::EnumString;
 
+use crate::aabb::Aabb;
+use crate::atmosphere::Atmosphere;
+use crate::bvh::Bvh;
+use crate::bvh::Stats;
 use crate::camera::Camera;
 use crate::hit::Hit;
+use crate::hit::Impact;
 use crate::image::Pixel;
 use crate::material;
 use crate::material::Dielectric;
+use crate::material::Hair;
 use crate::material::Lambertian;
+use crate::material::Light;
 use crate::material::Material;
 use crate::material::Metal;
+use crate::material::Phase;
+use crate::na;
 use crate::ray::Ray;
+use crate::shape;
+use crate::shape::Cage;
+use crate::shape::Capsule;
+use crate::shape::Cone;
+use crate::shape::ConstantMedium;
+use crate::shape::Csg;
+use crate::shape::Cuboid;
+use crate::shape::Curve;
+use crate::shape::Curves;
+use crate::shape::Cylinder;
+use crate::shape::Disk;
+use crate::shape::Face;
+use crate::shape::Field;
+use crate::shape::Grid;
+use crate::shape::Heightfield;
+use crate::shape::Heightmap;
+use crate::shape::Instance;
 use crate::shape::Intersect;
+use crate::shape::Mesh;
+use crate::shape::MovingSphere;
+use crate::shape::Plane;
+use crate::shape::Quad;
+use crate::shape::Sdf;
 use crate::shape::Sphere;
+use crate::shape::Torus;
+use crate::shape::Transformed;
+use crate::shape::Triangle;
+use crate::shape::Volume;
+use crate::texture::Bitmap;
+use crate::texture::Checker;
+use crate::texture::Noise;
+use crate::texture::Pattern;
 use crate::Vec3;
 
-#[derive(new)]
+use std::sync::Arc;
+
+/// Built-in scenes.
+#[derive(Clone, Copy, EnumString)]
+pub enum Preset {
+    /// Marbles around three balls
+    #[strum(serialize = "random")]
+    Random,
+    /// Ellipsoidal marbles instanced from a few shared spheres
+    #[strum(serialize = "crowd")]
+    Crowd,
+    /// Cylinders, cones, capsules, a torus and a subdivided die among marbles
+    #[strum(serialize = "shapes")]
+    Shapes,
+    /// Cornell box, lit from its ceiling
+    #[strum(serialize = "cornell")]
+    Cornell,
+    /// Cornell box holding a block of smoke and one of fog
+    #[strum(serialize = "smoke")]
+    Smoke,
+    /// Blends, twists and repetitions of distance fields
+    #[strum(serialize = "sdf")]
+    Sdf,
+    /// A furry ball in the grass
+    #[strum(serialize = "hair")]
+    Hair,
+    /// Marbles bouncing around three balls, blurred by the shutter
+    #[strum(serialize = "motion")]
+    Motion,
+    /// Marble, wood, cells and turbulence on balls over flagstones
+    #[strum(serialize = "noise")]
+    Noise,
+}
+
+/// What rays escaping the scene see.
+#[derive(Clone, Copy)]
+pub enum Background {
+    /// White to blue gradient
+    Sky,
+    Uniform(Vec3),
+}
+
 pub struct Scene<T> {
-    hitables: Vec<T>,
+    hitables: Bvh<T>,
+    background: Background,
+    atmosphere: Option<Atmosphere>,
 }
 
 impl<T> Scene<T> {
-    fn background(ray: &Ray) -> Vec3 {
-        let t = 0.5 * (1.0 + ray.direction.y);
-        let white = Vec3::new(1.0, 1.0, 1.0);
-        let blue = Vec3::new(0.5, 0.7, 1.0);
+    pub fn new(hitables: Vec<T>) -> Self
+    where
+        T: Hit,
+    {
+        Scene {
+            hitables: Bvh::new(hitables),
+            background: Background::Sky,
+            atmosphere: None,
+        }
+    }
+
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
 
-        (1.0 - t) * white + t * blue
+    /// Fills the space between surfaces with fog or haze.
+    pub fn with_atmosphere(mut self, atmosphere: Atmosphere) -> Self {
+        self.atmosphere = Some(atmosphere);
+        self
+    }
+
+    /// Statistics of the acceleration structure.
+    pub fn stats(&self) -> Stats
+    where
+        T: Hit,
+    {
+        self.hitables.stats()
+    }
+
+    fn background(&self, ray: &Ray) -> Vec3 {
+        match self.background {
+            Background::Sky => {
+                let t = 0.5 * (1.0 + ray.direction.y);
+                let white = Vec3::new(1.0, 1.0, 1.0);
+                let blue = Vec3::new(0.5, 0.7, 1.0);
+
+                (1.0 - t) * white + t * blue
+            }
+            Background::Uniform(color) => color,
+        }
     }
 
     fn color(&self, ray: Ray) -> Vec3
     where
         T: Hit,
     {
-        if let Some(impact) = self.hitables.hit(1e-6, std::f64::INFINITY, &ray) {
+        let impact = self.hitables.hit(1e-6, f64::INFINITY, &ray);
+
+        // Scattered by the atmosphere before reaching the surface or the sky
+        if let Some(atmosphere) = &self.atmosphere {
+            let max = impact.as_ref().map_or(f64::INFINITY, Impact::parameter);
+            if let Some(root) = atmosphere.collide(&ray, max) {
+                let point = ray.point_at(root);
+                let normal = -ray.direction.normalize();
+                let impact = Impact::new(root, point, normal, &atmosphere.phase);
+                return match (ray.is_active(), impact.scatter(ray)) {
+                    (true, Some(scattered)) => {
+                        let color = self.color(scattered.ray);
+                        scattered.attenuation.component_mul(&color)
+                    }
+                    _ => Vec3::zeros(),
+                };
+            }
+        }
+
+        if let Some(mut impact) = impact {
+            impact.differentiate(&ray);
+            let emitted = impact.emitted();
             if let (true, Some(scattered)) = (ray.is_active(), impact.scatter(ray)) {
                 let color = self.color(scattered.ray);
-                return scattered.attenuation.component_mul(&color);
+                return emitted + scattered.attenuation.component_mul(&color);
             }
 
-            return Vec3::zeros();
+            return emitted;
         }
 
-        Self::background(&ray)
+        self.background(&ray)
     }
 
-    pub fn sample(&self, camera: &Camera, pixel: Pixel) -> Vec3
+    pub fn sample(&self, camera: &Camera, pixel: Pixel, spacing: Pixel) -> Vec3
     where
         T: Hit,
     {
-        let ray = camera.gather(pixel);
+        let ray = camera.gather(pixel, spacing);
         self.color(ray)
     }
 }
 
-impl Scene<Sphere> {
-    pub fn random() -> Self {
+impl Scene<Instance> {
+    /// Thousands of marbles sharing a handful of unit spheres.
+    pub fn crowd() -> Self {
+        /// Number of marbles along each side
+        const SIDE: i32 = 100;
+        /// Distance between marbles
+        const SPACING: f64 = 0.4;
+
+        let prototypes: Vec<Arc<dyn Hit>> = (0..8)
+            .map(|_| Arc::new(Sphere::new(Vec3::zeros(), 1.0, material::random())) as _)
+            .collect();
+
+        let ground = Plane::new(
+            Vec3::zeros(),
+            Vec3::y(),
+            Lambertian::new(Vec3::new(0.5, 0.5, 0.5)).boxed(),
+        );
+        let ground = Instance::new(Arc::new(ground), na::Matrix4::identity());
+
+        let mut instances = vec![ground];
+
+        for a in (-SIDE / 2..SIDE / 2).map(f64::from) {
+            for b in (-SIDE / 2..SIDE / 2).map(f64::from) {
+                let prototype = &prototypes[rand::random::<usize>() % prototypes.len()];
+
+                let radius = 0.1 + 0.05 * rand::random::<f64>();
+                let scale = Vec3::new(radius, radius * (0.6 + 0.4 * rand::random::<f64>()), radius);
+                let x = SPACING * (a + 0.5 * rand::random::<f64>());
+                let z = SPACING * (b + 0.5 * rand::random::<f64>());
+                let angle = std::f64::consts::TAU * rand::random::<f64>();
+
+                let instance = Instance::new(Arc::clone(prototype), na::Matrix4::identity())
+                    .scaled(scale)
+                    .rotated(angle * Vec3::y())
+                    .translated(Vec3::new(x, scale.y, z));
+
+                instances.push(instance);
+            }
+        }
+
+        Scene::new(instances)
+    }
+}
+
+impl Scene<Box<dyn Hit>> {
+    /// Marbles on a `terrain`, flat if there is none, covered by `texture`,
+    /// and a `volume` of smoke or fire behind them.
+    pub fn random(
+        terrain: Option<Heightmap>,
+        texture: Option<Bitmap>,
+        volume: Option<Grid>,
+    ) -> Self {
         /// Ball radius
         const BALL: f64 = 1.0;
-        /// Ground radius
-        const GROUND: f64 = 1_000.0;
         /// Marble radius
         const MARBLE: f64 = 0.2;
+        /// Side of the terrain
+        const SIDE: f64 = 80.0;
+        /// Ground covered by one repetition of the texture
+        const TILE: f64 = 4.0;
 
-        let ground = Sphere::new(
-            Vec3::new(0.0, -GROUND, 0.0),
-            GROUND,
-            Lambertian::new(Vec3::new(0.5, 0.5, 0.5)).boxed(),
-        );
+        // Terrain coordinates span its whole side, plane ones are distances
+        let scale = if terrain.is_some() {
+            SIDE / TILE
+        } else {
+            TILE.recip()
+        };
+        let material = match texture {
+            Some(texture) => Lambertian::new(texture.with_scale(scale)).boxed(),
+            None => Lambertian::new(Vec3::new(0.5, 0.5, 0.5)).boxed(),
+        };
+        let ground = match terrain {
+            Some(heightmap) => {
+                let corner = Vec3::new(0.5 * SIDE, 1.0, 0.5 * SIDE);
+                let bounds = Aabb::new(-corner, corner);
+                Either::Left(Heightfield::new(heightmap, bounds, material))
+            }
+            None => Either::Right(Plane::new(Vec3::zeros(), Vec3::y(), material)),
+        };
+        let height = |x: f64, z: f64| ground.as_ref().left().map_or(0.0, |t| t.height(x, z));
 
         let centers = vec![
             Vec3::new(-4.0, BALL, 0.0),
@@ -80,8 +285,10 @@ impl Scene<Sphere> {
         ];
 
         let mut spheres: Vec<Sphere> = itertools::multizip((centers, materials))
-            .map(|(center, material)| Sphere::new(center, BALL, material))
-            .map(|sphere| sphere.stick_to(&ground))
+            .map(|(center, material)| {
+                let center = center + height(center.x, center.z) * Vec3::y();
+                Sphere::new(center, BALL, material)
+            })
             .collect();
 
         for a in (-11..11).map(f64::from) {
@@ -89,57 +296,644 @@ impl Scene<Sphere> {
                 loop {
                     let x = a + 0.9 * rand::random::<f64>();
                     let z = b + 0.9 * rand::random::<f64>();
-                    let center = Vec3::new(x, MARBLE, z);
+                    let center = Vec3::new(x, height(x, z) + MARBLE, z);
 
                     let sphere = Sphere::new(center, MARBLE, material::random());
                     if !spheres.intersect(&sphere) {
-                        spheres.push(sphere.stick_to(&ground));
+                        spheres.push(sphere);
                         break;
                     }
                 }
             }
         }
 
-        spheres.push(ground);
+        let mut hitables: Vec<_> = spheres.into_iter().map(Hit::boxed).collect();
+        if let Some(grid) = volume {
+            let bottom = height(-8.0, 0.5);
+            let bounds = Aabb::new(
+                Vec3::new(-10.0, bottom, -1.5),
+                Vec3::new(-6.0, bottom + 4.0, 2.5),
+            );
+            let phase = Phase::isotropic(Vec3::new(0.8, 0.8, 0.8)).boxed();
+            let volume = Volume::new(grid, bounds, 2.0, phase).with_emission(4.0);
+            hitables.push(volume.boxed());
+        }
+
+        hitables.push(ground.either(Hit::boxed, Hit::boxed));
 
-        Scene::new(spheres)
+        Scene::new(hitables)
     }
 
-    #[allow(dead_code)]
-    pub fn test() -> Self {
-        let hitables: Vec<_> = {
-            let centers = vec![
-                -Vec3::z(),
-                Vec3::new(0.0, -100.5, -1.0),
-                Vec3::new(1.0, 0.0, -1.0),
-                Vec3::new(-1.0, 0.0, -1.0),
-                Vec3::new(-1.0, 0.0, -1.0),
-                Vec3::z(),
-                // Vec3::new(-3.0, 0.0, -2.0),
-            ];
+    /// Analytic props with marbles laid out around them.
+    pub fn shapes() -> Self {
+        /// Marble radius
+        const MARBLE: f64 = 0.2;
+
+        let ground = Plane::new(
+            Vec3::zeros(),
+            Vec3::y(),
+            Lambertian::new(Checker::new(
+                Vec3::new(0.2, 0.3, 0.1),
+                Vec3::new(0.9, 0.9, 0.9),
+                1.0,
+            ))
+            .boxed(),
+        );
 
-            let radii = vec![
-                // 0.5, 100.0, 0.5, 0.5, 0.5,
-                0.5, 100.0, 0.5, 0.5, -0.499, 0.5,
-                // 1.0,
+        let pillar = Cylinder::new(
+            Vec3::new(-4.0, 0.0, 0.0),
+            Vec3::new(-4.0, 2.5, 0.0),
+            0.7,
+            Lambertian::new(Vec3::new(0.4, 0.2, 0.1)).boxed(),
+        );
+        let tube = Cylinder::new(
+            Vec3::zeros(),
+            Vec3::new(0.0, 1.8, 0.0),
+            0.9,
+            Metal::new(Vec3::new(0.7, 0.6, 0.5), 0.1).boxed(),
+        )
+        .uncapped();
+        let cone = Cone::new(
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(4.0, 2.2, 0.0),
+            1.0,
+            Dielectric::new(Vec3::new(1.0, 1.0, 1.0), 1.5).boxed(),
+        );
+        let funnel = Cone::new(
+            Vec3::new(-1.5, 1.2, 2.5),
+            Vec3::new(-1.5, 0.0, 2.5),
+            0.6,
+            Lambertian::new(Vec3::new(0.1, 0.3, 0.6)).boxed(),
+        )
+        .uncapped();
+        let capsule = Capsule::new(
+            Vec3::new(1.5, 0.5, 2.5),
+            Vec3::new(3.5, 0.5, 1.5),
+            0.5,
+            Metal::new(Vec3::new(0.8, 0.8, 0.9), 0.0).boxed(),
+        );
+        // Cube cage with its top edges creased, rounding to a die
+        let die = {
+            let positions = itertools::iproduct!([0.0, 1.0], [0.0, 1.0], [0.0, 1.0])
+                .map(|(x, y, z)| Vec3::new(5.8, 0.0, -0.8) + 1.2 * Vec3::new(x, y, z))
+                .collect();
+            let faces = vec![
+                vec![0, 1, 3, 2],
+                vec![4, 6, 7, 5],
+                vec![0, 4, 5, 1],
+                vec![2, 3, 7, 6],
+                vec![0, 2, 6, 4],
+                vec![1, 5, 7, 3],
             ];
+            Cage::new(positions, faces)
+                .with_crease(2, 3, f64::INFINITY)
+                .with_crease(3, 7, f64::INFINITY)
+                .with_crease(7, 6, f64::INFINITY)
+                .with_crease(6, 2, f64::INFINITY)
+                .tessellate(4, Lambertian::new(Vec3::new(0.9, 0.9, 0.8)).boxed())
+        };
+        let ring = Torus::new(
+            Vec3::new(1.5, 1.15, -2.5),
+            Vec3::new(1.0, 0.0, 0.3),
+            0.8,
+            0.3,
+            Metal::new(Vec3::new(0.9, 0.7, 0.3), 0.05).boxed(),
+        );
 
-            let materials = vec![
-                Lambertian::new(Vec3::new(0.8, 0.3, 0.3)).boxed(),
-                Lambertian::new(Vec3::new(0.8, 0.8, 0.0)).boxed(),
-                Metal::new(Vec3::new(0.8, 0.6, 0.2), 0.0).boxed(),
-                // Metal::new(Vec3::new(0.8, 0.8, 0.8), 1.0).boxed(),
-                Dielectric::new(Vec3::new(1.0, 1.0, 1.0), 1.5).boxed(),
+        // Sixteen squares around, eight from pole to pole
+        let globe = Sphere::new(
+            Vec3::new(2.5, 0.6, 2.0),
+            0.6,
+            Lambertian::new(Checker::new(
+                Vec3::new(0.1, 0.2, 0.5),
+                Vec3::new(0.9, 0.8, 0.3),
+                0.125,
+            ))
+            .boxed(),
+        );
+
+        let overlaps = |sphere: &Sphere| {
+            pillar.intersect(sphere)
+                || tube.intersect(sphere)
+                || cone.intersect(sphere)
+                || funnel.intersect(sphere)
+                || capsule.intersect(sphere)
+                || ring.intersect(sphere)
+                || die.intersect(sphere)
+                || globe.intersect(sphere)
+        };
+
+        let mut spheres: Vec<Sphere> = Vec::new();
+        for a in (-11..11).map(f64::from) {
+            for b in (-11..11).map(f64::from) {
+                let x = a + 0.9 * rand::random::<f64>();
+                let z = b + 0.9 * rand::random::<f64>();
+                let center = Vec3::new(x, MARBLE, z);
+
+                let sphere = Sphere::new(center, MARBLE, material::random());
+                if !overlaps(&sphere) && !spheres.intersect(&sphere) {
+                    spheres.push(sphere);
+                }
+            }
+        }
+
+        let mut hitables: Vec<_> = spheres.into_iter().map(Hit::boxed).collect();
+        hitables.push(ground.boxed());
+        hitables.push(pillar.boxed());
+        hitables.push(tube.boxed());
+        hitables.push(cone.boxed());
+        hitables.push(funnel.boxed());
+        hitables.push(capsule.boxed());
+        hitables.push(ring.boxed());
+        hitables.push(die.boxed());
+        hitables.push(globe.boxed());
+
+        Scene::new(hitables)
+    }
+
+    /// Distance fields blended, twisted and repeated on a ground.
+    pub fn sdf() -> Self {
+        let ground = Plane::new(
+            Vec3::zeros(),
+            Vec3::y(),
+            Lambertian::new(Vec3::new(0.5, 0.5, 0.5)).boxed(),
+        );
+
+        let blob = Field::sphere(0.8)
+            .translated(Vec3::new(0.0, 0.8, 0.0))
+            .smooth_union(
+                Field::rounded(Vec3::new(0.6, 0.4, 0.6), 0.15).translated(Vec3::new(0.6, 0.4, 0.9)),
+                0.4,
+            );
+        let blob = Sdf::new(
+            blob,
+            Aabb::new(Vec3::new(-1.0, 0.0, -1.0), Vec3::new(1.4, 1.8, 1.7)),
+            Metal::new(Vec3::new(0.8, 0.5, 0.4), 0.2).boxed(),
+        );
+
+        let column = Field::cuboid(Vec3::new(0.4, 1.2, 0.4))
+            .twist(1.2)
+            .translated(Vec3::new(-1.0, 1.2, -3.0));
+        let column = Sdf::new(
+            column,
+            Aabb::new(Vec3::new(-1.6, 0.0, -3.6), Vec3::new(-0.4, 2.4, -2.4)),
+            Lambertian::new(Vec3::new(0.2, 0.5, 0.3)).boxed(),
+        );
+
+        let ring = Field::torus(0.8, 0.25).translated(Vec3::new(2.5, 0.25, -1.5));
+        let ring = Sdf::new(
+            ring,
+            Aabb::new(Vec3::new(1.4, 0.0, -2.6), Vec3::new(3.6, 0.5, -0.4)),
+            Dielectric::new(Vec3::new(1.0, 1.0, 1.0), 1.5).boxed(),
+        );
+
+        // Cells are 0.6 wide, the bounds cutting between marbles
+        let marbles = Field::sphere(0.15)
+            .repeat(Vec3::new(0.6, 0.0, 0.6))
+            .translated(Vec3::new(0.0, 0.15, 0.0));
+        let marbles = Sdf::new(
+            marbles,
+            Aabb::new(Vec3::new(-6.3, 0.0, 2.1), Vec3::new(6.3, 0.3, 4.5)),
+            Lambertian::new(Vec3::new(0.6, 0.2, 0.2)).boxed(),
+        );
+
+        Scene::new(vec![
+            ground.boxed(),
+            blob.boxed(),
+            column.boxed(),
+            ring.boxed(),
+            marbles.boxed(),
+        ])
+    }
+
+    /// A furry ball on a ground covered with grass blades.
+    pub fn hair() -> Self {
+        /// Hairs on the ball
+        const HAIRS: usize = 15_000;
+        /// Blades of grass
+        const BLADES: usize = 15_000;
+
+        let ground = Plane::new(
+            Vec3::zeros(),
+            Vec3::y(),
+            Lambertian::new(Vec3::new(0.3, 0.25, 0.2)).boxed(),
+        );
+
+        let center = Vec3::new(0.0, 1.0, 0.0);
+        let ball = Sphere::new(
+            center,
+            0.7,
+            Lambertian::new(Vec3::new(0.1, 0.05, 0.02)).boxed(),
+        );
+
+        // Hairs growing out of the ball, drooping under their weight
+        let hairs = (0..HAIRS)
+            .map(|_| {
+                let normal = shape::random_on_unit_sphere();
+                let root = center + 0.7 * normal;
+                let length = 0.3 + 0.1 * rand::random::<f64>();
+                let droop = -0.3 * length * Vec3::y();
+                let points = [
+                    root,
+                    root + length / 3.0 * normal,
+                    root + 2.0 * length / 3.0 * normal + 0.3 * droop,
+                    root + length * normal + droop,
+                ];
+                Curve::cylinder(points, [0.01, 0.002])
+            })
+            .collect();
+        let fur = Curves::new(hairs, Hair::melanin(1.3, 0.6, 0.3, 0.3).boxed());
+
+        // Blades bending away from the ball
+        let blades = (0..BLADES)
+            .map(|_| {
+                let x = 2.0 + 6.0 * rand::random::<f64>();
+                let z = -3.0 + 6.0 * rand::random::<f64>();
+                let root = Vec3::new(x, 0.0, z);
+                let height = 0.3 + 0.3 * rand::random::<f64>();
+                let angle = std::f64::consts::TAU * rand::random::<f64>();
+                let bend = 0.3 * height * Vec3::new(angle.cos(), 0.0, angle.sin());
+                let points = [
+                    root,
+                    root + height / 3.0 * Vec3::y(),
+                    root + 2.0 * height / 3.0 * Vec3::y() + 0.5 * bend,
+                    root + height * Vec3::y() + bend,
+                ];
+                let normal = Vec3::new(angle.cos(), 0.0, angle.sin());
+                Curve::ribbon(points, [0.03, 0.0], [normal, normal])
+            })
+            .collect();
+        let grass = Curves::new(
+            blades,
+            Hair::colored(Vec3::new(0.2, 0.5, 0.1), 0.4, 0.6).boxed(),
+        );
+
+        Scene::new(vec![
+            ground.boxed(),
+            ball.boxed(),
+            fur.boxed(),
+            grass.boxed(),
+        ])
+    }
+
+    /// Marbles bouncing up from time 0 to time 1, resting at the top of their
+    /// bounce for shutters open longer.
+    pub fn motion() -> Self {
+        /// Ball radius
+        const BALL: f64 = 1.0;
+        /// Marble radius
+        const MARBLE: f64 = 0.2;
+
+        let ground = Plane::new(
+            Vec3::zeros(),
+            Vec3::y(),
+            Lambertian::new(Vec3::new(0.5, 0.5, 0.5)).boxed(),
+        );
+
+        let balls = vec![
+            Sphere::new(
+                Vec3::new(-4.0, BALL, 0.0),
+                BALL,
+                Lambertian::new(Vec3::new(0.4, 0.2, 0.1)).boxed(),
+            ),
+            Sphere::new(
+                Vec3::new(0.0, BALL, 0.0),
+                BALL,
                 Dielectric::new(Vec3::new(1.0, 1.0, 1.0), 1.5).boxed(),
-                Metal::new(Vec3::new(0.3, 0.3, 0.8), 0.5).boxed(),
-                // Metal::new(Vec3::new(224.0 / 255.0, 232.0 / 255.0, 222.0 / 255.0), 0.3).boxed(),
+            ),
+            Sphere::new(
+                Vec3::new(4.0, BALL, 0.0),
+                BALL,
+                Metal::new(Vec3::new(0.7, 0.6, 0.5), 0.0).boxed(),
+            ),
+        ];
+
+        // One per cell, far enough from its neighbours to bounce straight up
+        let mut marbles = Vec::new();
+        for a in (-11..11).map(f64::from) {
+            for b in (-11..11).map(f64::from) {
+                let x = a + 0.2 + 0.6 * rand::random::<f64>();
+                let z = b + 0.2 + 0.6 * rand::random::<f64>();
+                let center = Vec3::new(x, MARBLE, z);
+                let bounce = 0.5 * rand::random::<f64>() * Vec3::y();
+
+                let sphere = Sphere::new(center, MARBLE, material::random());
+                let marble = MovingSphere::new(sphere, bounce);
+                if !balls.intersect(&marble) {
+                    marbles.push(marble);
+                }
+            }
+        }
+
+        let mut hitables: Vec<_> = balls.into_iter().map(Hit::boxed).collect();
+        hitables.extend(marbles.into_iter().map(Hit::boxed));
+        hitables.push(ground.boxed());
+
+        Scene::new(hitables)
+    }
+
+    /// Procedural textures, the same every time.
+    pub fn noise() -> Self {
+        /// Ball radius
+        const BALL: f64 = 1.0;
+        /// Small ball radius
+        const SMALL: f64 = 0.5;
+
+        let flagstones = Noise::new(Pattern::Cells, 1, 0.5)
+            .with_colors(Vec3::new(0.1, 0.1, 0.1), Vec3::new(0.6, 0.55, 0.5));
+        let ground = Plane::new(
+            Vec3::zeros(),
+            Vec3::y(),
+            Lambertian::new(flagstones).boxed(),
+        );
+
+        let marble = Noise::new(Pattern::Marble, 2, 2.0)
+            .with_colors(Vec3::new(0.3, 0.3, 0.35), Vec3::new(0.9, 0.9, 0.85));
+        let wood = Noise::new(Pattern::Wood, 3, 5.0)
+            .with_colors(Vec3::new(0.6, 0.4, 0.15), Vec3::new(0.2, 0.1, 0.03));
+        // Patches of polished and brushed metal
+        let fuzz = Noise::new(Pattern::Turbulence(4), 4, 2.0);
+        let cells = Noise::new(Pattern::Worley, 5, 4.0)
+            .with_colors(Vec3::new(0.8, 0.2, 0.1), Vec3::new(0.9, 0.8, 0.2));
+        let clouds = Noise::new(Pattern::Perlin, 6, 4.0)
+            .with_colors(Vec3::new(0.1, 0.2, 0.6), Vec3::new(0.9, 0.9, 0.9));
+
+        let balls = vec![
+            Sphere::new(
+                Vec3::new(-4.0, BALL, 0.0),
+                BALL,
+                Lambertian::new(marble).boxed(),
+            ),
+            Sphere::new(
+                Vec3::new(0.0, BALL, 0.0),
+                BALL,
+                Lambertian::new(wood).boxed(),
+            ),
+            Sphere::new(
+                Vec3::new(4.0, BALL, 0.0),
+                BALL,
+                Metal::new(Vec3::new(0.8, 0.8, 0.8), fuzz).boxed(),
+            ),
+            Sphere::new(
+                Vec3::new(3.0, SMALL, 2.5),
+                SMALL,
+                Lambertian::new(cells).boxed(),
+            ),
+            Sphere::new(
+                Vec3::new(5.0, SMALL, 1.8),
+                SMALL,
+                Lambertian::new(clouds).boxed(),
+            ),
+        ];
+
+        let mut hitables: Vec<_> = balls.into_iter().map(Hit::boxed).collect();
+        hitables.push(ground.boxed());
+
+        Scene::new(hitables)
+    }
+
+    /// The standard Cornell box, 555 units wide, opened towards -z.
+    pub fn cornell() -> Self {
+        let white = || Lambertian::new(Vec3::new(0.73, 0.73, 0.73)).boxed();
+        let [tall, short] = cornell_blocks(white(), white());
+
+        let mut hitables: Vec<_> = cornell_walls().into_iter().map(Hit::boxed).collect();
+        hitables.push(tall.boxed());
+        hitables.push(short.boxed());
+
+        Scene::new(hitables).with_background(Background::Uniform(Vec3::zeros()))
+    }
+
+    /// Cornell box whose blocks are a dark smoke and a forward scattering fog.
+    pub fn smoke() -> Self {
+        let white = || Lambertian::new(Vec3::new(0.73, 0.73, 0.73)).boxed();
+        let [tall, short] = cornell_blocks(white(), white());
+
+        let smoke = ConstantMedium::new(
+            tall,
+            0.01,
+            Phase::isotropic(Vec3::new(0.1, 0.1, 0.1)).boxed(),
+        );
+        let fog = ConstantMedium::new(
+            short,
+            0.01,
+            Phase::henyey_greenstein(Vec3::new(0.9, 0.9, 0.9), 0.6).boxed(),
+        );
+
+        let mut hitables: Vec<_> = cornell_walls().into_iter().map(Hit::boxed).collect();
+        hitables.push(smoke.boxed());
+        hitables.push(fog.boxed());
+
+        Scene::new(hitables).with_background(Background::Uniform(Vec3::zeros()))
+    }
+
+    /// Places `model` on a ground.
+    pub fn model(model: Vec<Box<dyn Hit>>) -> Self {
+        let ground = Plane::new(
+            Vec3::zeros(),
+            Vec3::y(),
+            Lambertian::new(Vec3::new(0.5, 0.5, 0.5)).boxed(),
+        );
+
+        let mut hitables = vec![ground.boxed()];
+        hitables.extend(model);
+
+        Scene::new(hitables)
+    }
+
+    #[allow(dead_code)]
+    pub fn test() -> Self {
+        let hitables: Vec<_> = {
+            // Center, radius and material of each sphere
+            let spheres = vec![
+                (
+                    -Vec3::z(),
+                    0.5,
+                    Lambertian::new(Vec3::new(0.8, 0.3, 0.3)).boxed(),
+                ),
+                (
+                    Vec3::new(0.0, -100.5, -1.0),
+                    100.0,
+                    Lambertian::new(Vec3::new(0.8, 0.8, 0.0)).boxed(),
+                ),
+                (
+                    Vec3::new(1.0, 0.0, -1.0),
+                    0.5,
+                    Metal::new(Vec3::new(0.8, 0.6, 0.2), 0.0).boxed(),
+                ),
+                (
+                    Vec3::z(),
+                    0.5,
+                    Metal::new(Vec3::new(0.3, 0.3, 0.8), 0.5).boxed(),
+                ),
             ];
 
-            itertools::multizip((centers, radii, materials))
+            spheres
+                .into_iter()
                 .map(|(center, radius, material)| Sphere::new(center, radius, material))
+                .map(Hit::boxed)
                 .collect()
         };
 
+        let glass = || Dielectric::new(Vec3::new(1.0, 1.0, 1.0), 1.5).boxed();
+
+        let hollow = {
+            let center = Vec3::new(-1.0, 0.0, -1.0);
+            Csg::difference(
+                Sphere::new(center, 0.5, glass()),
+                Sphere::new(center, 0.45, glass()),
+            )
+        };
+
+        let lens = {
+            let center = Vec3::new(-1.0, 0.0, 1.0);
+            let offset = Vec3::new(0.45, 0.0, 0.0);
+            Csg::intersection(
+                Sphere::new(center - offset, 0.6, glass()),
+                Sphere::new(center + offset, 0.6, glass()),
+            )
+        };
+
+        let bowl = {
+            let center = Vec3::new(1.0, 0.0, 1.0);
+            let material = || Lambertian::new(Vec3::new(0.8, 0.2, 0.1)).boxed();
+            let shell = Csg::difference(
+                Sphere::new(center, 0.5, material()),
+                Sphere::new(center, 0.45, material()),
+            );
+            let lid = Cuboid::new(
+                center - Vec3::new(0.6, 0.0, 0.6),
+                center + Vec3::new(0.6, 0.6, 0.6),
+                material(),
+            );
+            Csg::difference(shell, lid)
+        };
+
+        let bubbles = {
+            let center = Vec3::new(2.5, -0.2, 0.0);
+            let offset = Vec3::new(0.0, 0.0, 0.2);
+            Csg::union(
+                Sphere::new(center - offset, 0.3, glass()),
+                Sphere::new(center + offset, 0.3, glass()),
+            )
+        };
+
+        let pyramid = {
+            let positions = vec![
+                Vec3::new(-0.5, 0.0, 0.5),
+                Vec3::new(0.5, 0.0, 0.5),
+                Vec3::new(0.5, 0.0, -0.5),
+                Vec3::new(-0.5, 0.0, -0.5),
+                Vec3::new(0.0, 1.0, 0.0),
+            ];
+
+            let faces = [[0, 1, 4], [1, 2, 4], [2, 3, 4], [3, 0, 4]]
+                .into_iter()
+                .map(|vertices| Face::new(vertices, None, None, 0))
+                .collect();
+
+            let materials = vec![Metal::new(Vec3::new(0.8, 0.8, 0.8), 0.1).boxed()];
+
+            let mesh = Mesh::new(positions, Vec::new(), Vec::new(), faces, materials);
+            let translation = Vec3::new(0.0, -0.5, -3.0);
+            let rotation = Vec3::y() * std::f64::consts::FRAC_PI_4;
+            Transformed::new(mesh, na::Isometry3::new(translation, rotation))
+        };
+
+        let ellipsoid = Sphere::new(
+            Vec3::zeros(),
+            1.0,
+            Metal::new(Vec3::new(0.9, 0.9, 0.9), 0.0).boxed(),
+        );
+        let ellipsoid = Transformed::new(ellipsoid, na::Matrix4::identity())
+            .scaled(Vec3::new(0.6, 0.2, 0.3))
+            .rotated(Vec3::z() * 0.3)
+            .translated(Vec3::new(1.5, 0.6, -2.0));
+
+        let triangle = Triangle::new(
+            [
+                Vec3::new(-2.0, -0.5, -3.0),
+                Vec3::new(2.0, -0.5, -3.0),
+                Vec3::new(0.0, 2.0, -4.0),
+            ],
+            Lambertian::new(Vec3::new(0.2, 0.6, 0.3)).boxed(),
+        )
+        .with_normals([
+            Vec3::new(-1.0, 0.0, 1.0),
+            Vec3::new(1.0, 0.0, 1.0),
+            Vec3::new(0.0, 1.0, 1.0),
+        ]);
+
+        let mirror = Disk::new(
+            Vec3::new(-1.5, 1.0, -3.0),
+            Vec3::new(1.0, 0.0, 1.0),
+            0.8,
+            Metal::new(Vec3::new(0.9, 0.9, 0.9), 0.0).boxed(),
+        );
+
+        let mut hitables = hitables;
+        hitables.push(hollow.boxed());
+        hitables.push(lens.boxed());
+        hitables.push(bowl.boxed());
+        hitables.push(bubbles.boxed());
+        hitables.push(mirror.boxed());
+        hitables.push(pyramid.boxed());
+        hitables.push(triangle.boxed());
+        hitables.push(ellipsoid.boxed());
+
         Scene::new(hitables)
     }
 }
+
+/// Side of the Cornell box.
+pub const CORNELL: f64 = 555.0;
+
+/// Walls of the Cornell box, lit from a hole in its ceiling.
+fn cornell_walls() -> Vec<Quad> {
+    let red = || Lambertian::new(Vec3::new(0.65, 0.05, 0.05)).boxed();
+    let white = || Lambertian::new(Vec3::new(0.73, 0.73, 0.73)).boxed();
+    let green = || Lambertian::new(Vec3::new(0.12, 0.45, 0.15)).boxed();
+    let light = Light::new(Vec3::new(15.0, 15.0, 15.0)).boxed();
+
+    let (x, y, z) = (
+        CORNELL * Vec3::x(),
+        CORNELL * Vec3::y(),
+        CORNELL * Vec3::z(),
+    );
+
+    vec![
+        Quad::new(x, y, z, green()),
+        Quad::new(Vec3::zeros(), y, z, red()),
+        Quad::new(Vec3::zeros(), x, z, white()),
+        Quad::new(x + y + z, -x, -z, white()),
+        Quad::new(z, x, y, white()),
+        Quad::new(
+            Vec3::new(213.0, CORNELL - 1.0, 227.0),
+            Vec3::new(130.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 105.0),
+            light,
+        ),
+    ]
+}
+
+/// Tall and short blocks standing in the Cornell box.
+fn cornell_blocks(tall: Box<dyn Material>, short: Box<dyn Material>) -> [Transformed<Cuboid>; 2] {
+    let tall = Cuboid::new(Vec3::zeros(), Vec3::new(165.0, 330.0, 165.0), tall);
+    let tall = Transformed::new(
+        tall,
+        na::Isometry3::new(
+            Vec3::new(265.0, 0.0, 295.0),
+            15_f64.to_radians() * Vec3::y(),
+        ),
+    );
+    let short = Cuboid::new(Vec3::zeros(), Vec3::new(165.0, 165.0, 165.0), short);
+    let short = Transformed::new(
+        short,
+        na::Isometry3::new(
+            Vec3::new(130.0, 0.0, 65.0),
+            -18_f64.to_radians() * Vec3::y(),
+        ),
+    );
+
+    [tall, short]
+}
@@ -1,7 +1,60 @@
+mod capsule;
+mod cone;
+mod csg;
+mod cuboid;
+mod curve;
+mod cylinder;
+mod displacement;
+mod heightfield;
+mod medium;
+mod mesh;
+mod moving;
+mod plane;
+mod quad;
+mod sdf;
 mod sphere;
+mod subdivision;
+mod torus;
+mod transformed;
+mod triangle;
+mod volume;
 
+pub use crate::shape::capsule::*;
+pub use crate::shape::cone::*;
+pub use crate::shape::csg::*;
+pub use crate::shape::cuboid::*;
+pub use crate::shape::curve::*;
+pub use crate::shape::cylinder::*;
+pub use crate::shape::displacement::*;
+pub use crate::shape::heightfield::*;
+pub use crate::shape::medium::*;
+pub use crate::shape::mesh::*;
+pub use crate::shape::moving::*;
+pub use crate::shape::plane::*;
+pub use crate::shape::quad::*;
+pub use crate::shape::sdf::*;
 pub use crate::shape::sphere::*;
+pub use crate::shape::subdivision::*;
+pub use crate::shape::torus::*;
+pub use crate::shape::transformed::*;
+pub use crate::shape::triangle::Triangle;
+pub use crate::shape::volume::*;
+
+use crate::Vec3;
 
 pub trait Intersect<S = Self> {
     fn intersect(&self, other: &S) -> bool;
 }
+
+/// Extent along each axis of a disk of `radius` facing the unit `axis`.
+fn disk_extent(axis: &Vec3, radius: f64) -> Vec3 {
+    axis.map(|a| radius * (1.0 - a.powi(2)).max(0.0).sqrt())
+}
+
+/// Distance from `point` to the segment `ab`, and the segment parameter of
+/// the closest point.
+fn segment_distance(a: &Vec3, b: &Vec3, point: &Vec3) -> (f64, f64) {
+    let ab = b - a;
+    let t = ((point - a).dot(&ab) / ab.norm_squared()).clamp(0.0, 1.0);
+    ((a + t * ab - point).norm(), t)
+}
@@ -0,0 +1,91 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::polynomial;
+use crate::ray::Ray;
+use crate::shape;
+use crate::shape::cylinder;
+use crate::shape::Intersect;
+use crate::shape::Sphere;
+use crate::Vec3;
+
+/// Points within `radius` of the segment from `a` to `b`, a sphere when
+/// both meet.
+pub struct Capsule {
+    a: Vec3,
+    b: Vec3,
+    radius: f64,
+    material: Box<dyn Material>,
+}
+
+impl Capsule {
+    pub fn new(a: Vec3, b: Vec3, radius: f64, material: Box<dyn Material>) -> Capsule {
+        Capsule {
+            a,
+            b,
+            radius,
+            material,
+        }
+    }
+
+    /// Closest hit of the hemisphere around `center`, bulging `outward`.
+    fn end(
+        &self,
+        center: &Vec3,
+        outward: &Vec3,
+        min: f64,
+        max: f64,
+        ray: &Ray,
+    ) -> Option<(f64, Vec3)> {
+        let centered = ray.origin - center;
+        let a = ray.direction.norm_squared();
+        let b = 2.0 * ray.direction.dot(&centered);
+        let c = centered.norm_squared() - self.radius.powi(2);
+
+        polynomial::quadratic(a, b, c).into_iter().find_map(|root| {
+            let normal = (ray.point_at(root) - center) / self.radius;
+            let valid = min <= root && root <= max && normal.dot(outward) >= 0.0;
+            valid.then_some((root, normal))
+        })
+    }
+}
+
+impl hit::Hit for Capsule {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let axis = self.b - self.a;
+        let height = axis.norm();
+        // Without a side, both ends then make whole spheres
+        let (axis, side) = match axis.try_normalize(0.0) {
+            Some(axis) => {
+                let side = cylinder::side(&self.a, &axis, height, self.radius, min, max, ray);
+                (axis, side)
+            }
+            None => (Vec3::zeros(), None),
+        };
+        let max = side.map_or(max, |(root, _)| root);
+        let a = self.end(&self.a, &-axis, min, max, ray);
+        let max = a.map_or(max, |(root, _)| root);
+        let b = self.end(&self.b, &axis, min, max, ray);
+
+        let (root, normal) = b.or(a).or(side)?;
+        let point = ray.point_at(root);
+        let material = self.material.as_ref();
+
+        Some(hit::Impact::new(root, point, normal, material))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let radius = Vec3::repeat(self.radius);
+        let a = Aabb::new(self.a - radius, self.a + radius);
+        let b = Aabb::new(self.b - radius, self.b + radius);
+
+        Some(a.union(&b))
+    }
+}
+
+impl Intersect<Sphere> for Capsule {
+    fn intersect(&self, other: &Sphere) -> bool {
+        let (distance, _) = shape::segment_distance(&self.a, &self.b, other.center());
+        distance < self.radius + other.radius()
+    }
+}
@@ -0,0 +1,129 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::polynomial;
+use crate::ray::Ray;
+use crate::shape;
+use crate::shape::cylinder;
+use crate::shape::Intersect;
+use crate::shape::Sphere;
+use crate::Vec3;
+
+/// Cone from a disk of `radius` around `base` to an `apex`.
+pub struct Cone {
+    base: Vec3,
+    apex: Vec3,
+    /// Unit axis, from apex to base.
+    axis: Vec3,
+    height: f64,
+    radius: f64,
+    capped: bool,
+    material: Box<dyn Material>,
+}
+
+impl Cone {
+    /// `base` and `apex` must differ, the axis following from them.
+    pub fn new(base: Vec3, apex: Vec3, radius: f64, material: Box<dyn Material>) -> Cone {
+        let axis = base - apex;
+        assert!(axis.norm() > 0.0);
+
+        Cone {
+            base,
+            apex,
+            axis: axis.normalize(),
+            height: axis.norm(),
+            radius,
+            capped: true,
+            material,
+        }
+    }
+
+    /// Open cone, without its base disk.
+    pub fn uncapped(mut self) -> Self {
+        self.capped = false;
+        self
+    }
+
+    /// Slope of the side, radius over height.
+    fn slope(&self) -> f64 {
+        self.radius / self.height
+    }
+
+    fn side(&self, min: f64, max: f64, ray: &Ray) -> Option<(f64, Vec3)> {
+        let axis = &self.axis;
+        let k = 1.0 + self.slope().powi(2);
+
+        // |v|² = k (v·axis)² with v relative to the apex
+        let centered = ray.origin - self.apex;
+        let da = ray.direction.dot(axis);
+        let ca = centered.dot(axis);
+
+        let a = ray.direction.norm_squared() - k * da.powi(2);
+        let b = 2.0 * (ray.direction.dot(&centered) - k * da * ca);
+        let c = centered.norm_squared() - k * ca.powi(2);
+
+        polynomial::quadratic(a, b, c).into_iter().find_map(|root| {
+            let v = centered + root * ray.direction;
+            let h = v.dot(axis);
+            // The other nappe is behind the apex
+            if root < min || max < root || h < 0.0 || self.height < h {
+                return None;
+            }
+
+            let normal = (v - k * h * axis).normalize();
+            Some((root, normal))
+        })
+    }
+}
+
+impl hit::Hit for Cone {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let mut closest = self.side(min, max, ray);
+        if self.capped {
+            let max = closest.map_or(max, |(root, _)| root);
+            let base = cylinder::cap(&self.base, &self.axis, self.radius, min, max, ray);
+            closest = base.or(closest);
+        }
+
+        let (root, normal) = closest?;
+        let point = ray.point_at(root);
+        let material = self.material.as_ref();
+
+        Some(hit::Impact::new(root, point, normal, material))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = shape::disk_extent(&self.axis, self.radius);
+        let base = Aabb::new(self.base - extent, self.base + extent);
+
+        Some(base.grow(&self.apex))
+    }
+}
+
+impl Intersect<Sphere> for Cone {
+    /// Overlap with the solid cone.
+    fn intersect(&self, other: &Sphere) -> bool {
+        let offset = other.center() - self.apex;
+        let h = offset.dot(&self.axis);
+        let r = (offset - h * self.axis).norm();
+
+        // The solid is the triangle apex, base center, base rim in the
+        // (height, radius) half plane
+        let inside = (0.0..=self.height).contains(&h) && r <= self.slope() * h;
+        if inside {
+            return true;
+        }
+
+        let point = Vec3::new(h, r, 0.0);
+        let apex = Vec3::zeros();
+        let center = Vec3::new(self.height, 0.0, 0.0);
+        let rim = Vec3::new(self.height, self.radius, 0.0);
+
+        let distance = [(apex, rim), (rim, center), (center, apex)]
+            .iter()
+            .map(|(a, b)| shape::segment_distance(a, b, &point).0)
+            .fold(f64::INFINITY, f64::min);
+
+        distance < other.radius()
+    }
+}
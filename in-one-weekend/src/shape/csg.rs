@@ -0,0 +1,148 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::hit::Hit;
+use crate::ray::Ray;
+
+/// Offset past a boundary before looking for the next one.
+const EPSILON: f64 = 1e-6;
+/// Boundaries crossed at most, guarding against shapes never letting go.
+const MAX_CROSSINGS: usize = 64;
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl Operation {
+    fn inside(self, left: bool, right: bool) -> bool {
+        match self {
+            Operation::Union => left || right,
+            Operation::Intersection => left && right,
+            Operation::Difference => left && !right,
+        }
+    }
+}
+
+/// Constructive solid geometry combining two closed shapes.
+///
+/// The shapes must have outward normals: a boundary is entered when the ray
+/// goes against its normal and left otherwise.
+pub struct Csg<L, R> {
+    operation: Operation,
+    left: L,
+    right: R,
+}
+
+impl<L, R> Csg<L, R>
+where
+    L: Hit,
+    R: Hit,
+{
+    /// Points inside either shape.
+    pub fn union(left: L, right: R) -> Csg<L, R> {
+        Csg {
+            operation: Operation::Union,
+            left,
+            right,
+        }
+    }
+
+    /// Points inside both shapes.
+    pub fn intersection(left: L, right: R) -> Csg<L, R> {
+        Csg {
+            operation: Operation::Intersection,
+            left,
+            right,
+        }
+    }
+
+    /// Points inside `left` but not `right`.
+    pub fn difference(left: L, right: R) -> Csg<L, R> {
+        Csg {
+            operation: Operation::Difference,
+            left,
+            right,
+        }
+    }
+}
+
+/// Next boundary of `shape` after `min`, and whether the ray enters it.
+fn cross<'h, T>(shape: &'h T, min: f64, ray: &Ray) -> Option<(hit::Impact<'h>, bool)>
+where
+    T: Hit,
+{
+    let impact = shape.hit(min, f64::INFINITY, ray)?;
+    let entering = ray.direction.dot(&impact.normal).is_sign_negative();
+    Some((impact, entering))
+}
+
+impl<L, R> Hit for Csg<L, R>
+where
+    L: Hit,
+    R: Hit,
+{
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        // Boundaries are searched past `max` to know whether the ray starts
+        // inside a shape
+        let mut left = cross(&self.left, min, ray);
+        let mut right = cross(&self.right, min, ray);
+        let mut in_left = left.as_ref().is_some_and(|(_, entering)| !entering);
+        let mut in_right = right.as_ref().is_some_and(|(_, entering)| !entering);
+
+        for _ in 0..MAX_CROSSINGS {
+            let is_left = match (&left, &right) {
+                (Some((l, _)), Some((r, _))) => l.parameter() <= r.parameter(),
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => return None,
+            };
+
+            let next = if is_left { &mut left } else { &mut right };
+            let (mut impact, entering) = next.take()?;
+            if impact.parameter() > max {
+                return None;
+            }
+
+            let before = self.operation.inside(in_left, in_right);
+            if is_left {
+                in_left = entering;
+            } else {
+                in_right = entering;
+            }
+            let after = self.operation.inside(in_left, in_right);
+
+            if before != after {
+                // Carved surfaces face into the removed shape
+                if !is_left && matches!(self.operation, Operation::Difference) {
+                    impact.normal = -impact.normal;
+                }
+                return Some(impact);
+            }
+
+            let parameter = impact.parameter() + EPSILON;
+            if is_left {
+                left = cross(&self.left, parameter, ray);
+            } else {
+                right = cross(&self.right, parameter, ray);
+            }
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let left = self.left.bounding_box();
+        let right = self.right.bounding_box();
+
+        match self.operation {
+            Operation::Union => Some(left?.union(&right?)),
+            Operation::Intersection => match (left, right) {
+                (Some(left), Some(right)) => Some(left.intersection(&right)),
+                (left, right) => left.or(right),
+            },
+            Operation::Difference => left,
+        }
+    }
+}
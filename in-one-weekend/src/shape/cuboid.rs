@@ -0,0 +1,90 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::na;
+use crate::ray::Ray;
+use crate::shape::Intersect;
+use crate::shape::Sphere;
+use crate::Vec3;
+
+/// Axis-aligned box, rotated boxes being `Transformed` ones.
+pub struct Cuboid {
+    aabb: Aabb,
+    material: Box<dyn Material>,
+}
+
+impl Cuboid {
+    /// Box between two opposite corners.
+    pub fn new(a: Vec3, b: Vec3, material: Box<dyn Material>) -> Cuboid {
+        Cuboid {
+            aabb: Aabb::new(a, b),
+            material,
+        }
+    }
+}
+
+impl hit::Hit for Cuboid {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let Aabb {
+            min: low,
+            max: high,
+        } = &self.aabb;
+
+        let mut entry = (f64::NEG_INFINITY, 0);
+        let mut exit = (f64::INFINITY, 0);
+        for axis in 0..3 {
+            let inverse = ray.direction[axis].recip();
+            let t0 = (low[axis] - ray.origin[axis]) * inverse;
+            let t1 = (high[axis] - ray.origin[axis]) * inverse;
+            let (t0, t1) = if t0 <= t1 { (t0, t1) } else { (t1, t0) };
+
+            if t0 > entry.0 {
+                entry = (t0, axis);
+            }
+            if t1 < exit.0 {
+                exit = (t1, axis);
+            }
+        }
+
+        if exit.0 < entry.0 {
+            return None;
+        }
+
+        // Outward normal along the crossed face
+        let (root, axis, sign) = if min <= entry.0 && entry.0 <= max {
+            (entry.0, entry.1, -ray.direction[entry.1].signum())
+        } else if min <= exit.0 && exit.0 <= max {
+            (exit.0, exit.1, ray.direction[exit.1].signum())
+        } else {
+            return None;
+        };
+
+        let point = ray.point_at(root);
+        let mut normal = Vec3::zeros();
+        normal[axis] = sign;
+        let material = self.material.as_ref();
+
+        let mut impact = hit::Impact::new(root, point, normal, material);
+        impact.curvature = Some(na::Matrix3::zeros());
+        Some(impact)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.aabb)
+    }
+}
+
+impl Intersect for Cuboid {
+    fn intersect(&self, other: &Cuboid) -> bool {
+        let (a, b) = (&self.aabb, &other.aabb);
+        (0..3).all(|axis| a.min[axis] < b.max[axis] && b.min[axis] < a.max[axis])
+    }
+}
+
+impl Intersect<Sphere> for Cuboid {
+    fn intersect(&self, other: &Sphere) -> bool {
+        let center = other.center();
+        let closest = center.sup(&self.aabb.min).inf(&self.aabb.max);
+        (closest - center).norm() < other.radius()
+    }
+}
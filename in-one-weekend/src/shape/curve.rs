@@ -0,0 +1,291 @@
+use crate::aabb::Aabb;
+use crate::bvh::Tree;
+use crate::hit;
+use crate::material::Material;
+use crate::na;
+use crate::ray::Ray;
+use crate::Vec3;
+
+/// Subdivisions of a curve at most before it is taken as a segment.
+const MAX_DEPTH: i32 = 10;
+
+#[derive(Clone, Copy)]
+enum Profile {
+    /// Round fiber, shaded as a thin tube.
+    Cylinder,
+    /// Flat strip facing its normals at both ends.
+    Ribbon([Vec3; 2]),
+}
+
+/// Cubic Bézier segment, its width varying linearly from start to end.
+#[derive(Clone, Copy)]
+pub struct Curve {
+    points: [Vec3; 4],
+    widths: [f64; 2],
+    profile: Profile,
+}
+
+impl Curve {
+    /// Fiber of round section, for hair and fur.
+    pub fn cylinder(points: [Vec3; 4], widths: [f64; 2]) -> Curve {
+        Curve {
+            points,
+            widths,
+            profile: Profile::Cylinder,
+        }
+    }
+
+    /// Flat strip, for grass and leaves.
+    pub fn ribbon(points: [Vec3; 4], widths: [f64; 2], normals: [Vec3; 2]) -> Curve {
+        Curve {
+            points,
+            widths,
+            profile: Profile::Ribbon(normals.map(|normal| normal.normalize())),
+        }
+    }
+
+    fn width(&self, u: f64) -> f64 {
+        self.widths[0] + u * (self.widths[1] - self.widths[0])
+    }
+
+    fn bounds(&self) -> Aabb {
+        let radius = 0.5 * self.widths[0].max(self.widths[1]);
+        let bounds = Aabb::from_points(&self.points);
+        Aabb::new(
+            bounds.min.add_scalar(-radius),
+            bounds.max.add_scalar(radius),
+        )
+    }
+
+    /// Ribbon normal at `u`, spherically interpolated.
+    fn normal(normals: &[Vec3; 2], u: f64) -> Vec3 {
+        let [a, b] = normals;
+        let angle = a.dot(b).clamp(-1.0, 1.0).acos();
+        if angle < 1e-6 {
+            return *a;
+        }
+
+        (a * ((1.0 - u) * angle).sin() + b * (u * angle).sin()) / angle.sin()
+    }
+}
+
+fn lerp(t: f64, a: &Vec3, b: &Vec3) -> Vec3 {
+    a + t * (b - a)
+}
+
+/// Splits the Bézier control points at their middle.
+fn split(points: &[Vec3; 4]) -> [[Vec3; 4]; 2] {
+    let [p0, p1, p2, p3] = points;
+    let middle = (p0 + 3.0 * p1 + 3.0 * p2 + p3) / 8.0;
+    [
+        [*p0, (p0 + p1) / 2.0, (p0 + 2.0 * p1 + p2) / 4.0, middle],
+        [middle, (p1 + 2.0 * p2 + p3) / 4.0, (p2 + p3) / 2.0, *p3],
+    ]
+}
+
+/// Point and derivative of the Bézier curve at `u`.
+fn evaluate(points: &[Vec3; 4], u: f64) -> (Vec3, Vec3) {
+    let [p0, p1, p2, p3] = points;
+    let [a, b, c] = [lerp(u, p0, p1), lerp(u, p1, p2), lerp(u, p2, p3)];
+    let [d, e] = [lerp(u, &a, &b), lerp(u, &b, &c)];
+
+    let derivative = if (e - d).norm_squared() > 0.0 {
+        3.0 * (e - d)
+    } else {
+        p3 - p0
+    };
+    (lerp(u, &d, &e), derivative)
+}
+
+/// Closest hit of the curve within ray space, where the ray starts at the
+/// origin along z.
+struct Crossing {
+    /// Distance along the ray.
+    depth: f64,
+    u: f64,
+    /// Distance to the axis.
+    distance: f64,
+    width: f64,
+}
+
+impl Curve {
+    fn cross(
+        &self,
+        points: &[Vec3; 4],
+        range: (f64, f64),
+        near: f64,
+        far: f64,
+        depth: i32,
+        direction: &Vec3,
+    ) -> Option<Crossing> {
+        let (u0, u1) = range;
+        let radius = 0.5 * self.width(u0).max(self.width(u1));
+        let bounds = Aabb::from_points(points);
+        if bounds.min.x - radius > 0.0
+            || bounds.max.x + radius < 0.0
+            || bounds.min.y - radius > 0.0
+            || bounds.max.y + radius < 0.0
+            || bounds.min.z - radius > far
+            || bounds.max.z + radius < near
+        {
+            return None;
+        }
+
+        if depth > 0 {
+            let middle = 0.5 * (u0 + u1);
+            let [first, second] = split(points);
+            let first = self.cross(&first, (u0, middle), near, far, depth - 1, direction);
+            let far = first.as_ref().map_or(far, |crossing| crossing.depth);
+            let second = self.cross(&second, (middle, u1), near, far, depth - 1, direction);
+            return second.or(first);
+        }
+
+        let [p0, p1, p2, p3] = points;
+
+        // Beyond the start or end of the segment
+        if (p1.y - p0.y) * -p0.y + p0.x * (p0.x - p1.x) < 0.0 {
+            return None;
+        }
+        if (p2.y - p3.y) * -p3.y + p3.x * (p3.x - p2.x) < 0.0 {
+            return None;
+        }
+
+        // Closest point of the segment to the ray
+        let segment = (p3 - p0).xy();
+        let length2 = segment.norm_squared();
+        if length2 == 0.0 {
+            return None;
+        }
+        let w = (-p0.xy().dot(&segment) / length2).clamp(0.0, 1.0);
+        let u = (u0 + w * (u1 - u0)).clamp(u0, u1);
+
+        let mut width = self.width(u);
+        if let Profile::Ribbon(normals) = &self.profile {
+            width *= Curve::normal(normals, u).dot(direction).abs();
+        }
+
+        let (point, _) = evaluate(points, w);
+        let distance2 = point.xy().norm_squared();
+        if distance2 > 0.25 * width.powi(2) || point.z < near || point.z > far {
+            return None;
+        }
+
+        Some(Crossing {
+            depth: point.z,
+            u,
+            distance: distance2.sqrt(),
+            width,
+        })
+    }
+
+    /// Hits the curve, `frame` rotating world directions into ray space.
+    fn hit<'m>(
+        &self,
+        min: f64,
+        max: f64,
+        ray: &Ray,
+        frame: &na::Matrix3<f64>,
+        material: &'m dyn Material,
+    ) -> Option<hit::Impact<'m>> {
+        let speed = ray.direction.norm();
+        let points = self.points.map(|point| frame * (point - ray.origin));
+
+        // Deep enough for segments to be within a twentieth of the width
+        let flatness = (0..2)
+            .map(|i| (points[i] - 2.0 * points[i + 1] + points[i + 2]).amax())
+            .fold(0.0, f64::max);
+        let epsilon = 0.05 * self.widths[0].max(self.widths[1]);
+        let depth = if flatness > 0.0 && epsilon > 0.0 {
+            let depth = (std::f64::consts::SQRT_2 * 6.0 * flatness / (8.0 * epsilon)).log2() / 2.0;
+            (depth.round() as i32).clamp(0, MAX_DEPTH)
+        } else {
+            0
+        };
+
+        let direction = ray.direction / speed;
+        let crossing = self.cross(
+            &points,
+            (0.0, 1.0),
+            min * speed,
+            max * speed,
+            depth,
+            &direction,
+        )?;
+
+        let (center, tangent) = evaluate(&self.points, crossing.u);
+        let tangent = tangent.normalize();
+        let (root, normal) = match &self.profile {
+            Profile::Cylinder => {
+                // Front of the tube rather than its axis
+                let radius = 0.5 * crossing.width;
+                let offset = (radius.powi(2) - crossing.distance.powi(2)).max(0.0).sqrt();
+                let root = (crossing.depth - offset) / speed;
+                // Leaving the fiber
+                if root < min {
+                    return None;
+                }
+                let radial = ray.point_at(root) - center;
+                let normal = radial - radial.dot(&tangent) * tangent;
+                let normal = normal.try_normalize(0.0).unwrap_or(-direction);
+                (root, normal)
+            }
+            Profile::Ribbon(normals) => {
+                // Segments only approximate the curve, rays leaving it could
+                // hit it again within its width
+                if crossing.depth < (min * speed) + 0.5 * self.width(crossing.u) {
+                    return None;
+                }
+                (crossing.depth / speed, Curve::normal(normals, crossing.u))
+            }
+        };
+
+        let point = ray.point_at(root);
+        let mut impact = hit::Impact::new(root, point, normal, material);
+        impact.tangent = Some(tangent);
+        Some(impact)
+    }
+}
+
+/// Curves sharing a material, such as the hairs of a fur.
+pub struct Curves {
+    curves: Vec<Curve>,
+    material: Box<dyn Material>,
+    tree: Tree,
+}
+
+impl Curves {
+    pub fn new(curves: Vec<Curve>, material: Box<dyn Material>) -> Curves {
+        let bounds: Vec<_> = curves.iter().map(Curve::bounds).collect();
+        let tree = Tree::new(&bounds);
+
+        Curves {
+            curves,
+            material,
+            tree,
+        }
+    }
+}
+
+impl hit::Hit for Curves {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        // Rows are the axes of ray space, z along the ray
+        let z = ray.direction.normalize();
+        let x = if z.x.abs() > z.y.abs() {
+            Vec3::new(-z.z, 0.0, z.x)
+        } else {
+            Vec3::new(0.0, z.z, -z.y)
+        }
+        .normalize();
+        let y = z.cross(&x);
+        let frame = na::Matrix3::from_rows(&[x.transpose(), y.transpose(), z.transpose()]);
+
+        let material = self.material.as_ref();
+        self.tree.hit(min, max, ray, |i, min, max| {
+            self.curves[i].hit(min, max, ray, &frame, material)
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.tree.bounds())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::polynomial;
+use crate::ray::Ray;
+use crate::shape;
+use crate::shape::Intersect;
+use crate::shape::Sphere;
+use crate::Vec3;
+
+/// Cylinder of `radius` around the segment from `base` to `top`.
+pub struct Cylinder {
+    base: Vec3,
+    /// Unit axis, from base to top.
+    axis: Vec3,
+    height: f64,
+    radius: f64,
+    capped: bool,
+    material: Box<dyn Material>,
+}
+
+impl Cylinder {
+    /// `base` and `top` must differ, the axis following from them.
+    pub fn new(base: Vec3, top: Vec3, radius: f64, material: Box<dyn Material>) -> Cylinder {
+        let axis = top - base;
+        assert!(axis.norm() > 0.0);
+
+        Cylinder {
+            base,
+            axis: axis.normalize(),
+            height: axis.norm(),
+            radius,
+            capped: true,
+            material,
+        }
+    }
+
+    /// Open tube, without its two disks.
+    pub fn uncapped(mut self) -> Self {
+        self.capped = false;
+        self
+    }
+
+    fn top(&self) -> Vec3 {
+        self.base + self.height * self.axis
+    }
+}
+
+/// Closest hit of the side of the infinite cylinder of unit `axis` through
+/// `base`, limited to heights in `[0, height]`.
+pub fn side(
+    base: &Vec3,
+    axis: &Vec3,
+    height: f64,
+    radius: f64,
+    min: f64,
+    max: f64,
+    ray: &Ray,
+) -> Option<(f64, Vec3)> {
+    let centered = ray.origin - base;
+    let direction = ray.direction - ray.direction.dot(axis) * axis;
+    let offset = centered - centered.dot(axis) * axis;
+
+    let a = direction.norm_squared();
+    let b = 2.0 * direction.dot(&offset);
+    let c = offset.norm_squared() - radius.powi(2);
+
+    polynomial::quadratic(a, b, c).into_iter().find_map(|root| {
+        let h = (centered + root * ray.direction).dot(axis);
+        if root < min || max < root || h < 0.0 || height < h {
+            return None;
+        }
+
+        let point = ray.point_at(root);
+        let normal = (point - base - h * axis) / radius;
+        Some((root, normal))
+    })
+}
+
+/// Hit of the disk of `radius` around `center`, facing `normal`.
+pub fn cap(
+    center: &Vec3,
+    normal: &Vec3,
+    radius: f64,
+    min: f64,
+    max: f64,
+    ray: &Ray,
+) -> Option<(f64, Vec3)> {
+    let root = shape::plane_crossing(center, normal, min, max, ray)?;
+    let inside = (ray.point_at(root) - center).norm_squared() <= radius.powi(2);
+    inside.then_some((root, *normal))
+}
+
+impl hit::Hit for Cylinder {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let (base, axis, radius) = (&self.base, &self.axis, self.radius);
+
+        let mut closest = side(base, axis, self.height, radius, min, max, ray);
+        if self.capped {
+            let max = closest.map_or(max, |(root, _)| root);
+            let bottom = cap(base, &-axis, radius, min, max, ray);
+            let max = bottom.map_or(max, |(root, _)| root);
+            let top = cap(&self.top(), axis, radius, min, max, ray);
+            closest = top.or(bottom).or(closest);
+        }
+
+        let (root, normal) = closest?;
+        let point = ray.point_at(root);
+        let material = self.material.as_ref();
+
+        Some(hit::Impact::new(root, point, normal, material))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = shape::disk_extent(&self.axis, self.radius);
+        let bottom = Aabb::new(self.base - extent, self.base + extent);
+        let top = Aabb::new(self.top() - extent, self.top() + extent);
+
+        Some(bottom.union(&top))
+    }
+}
+
+impl Intersect<Sphere> for Cylinder {
+    /// Overlap with the solid cylinder.
+    fn intersect(&self, other: &Sphere) -> bool {
+        let offset = other.center() - self.base;
+        let h = offset.dot(&self.axis);
+        let radial = offset - h * self.axis;
+        let distance = radial.norm();
+
+        // Closest point of the solid, in axial and radial coordinates
+        let closest_h = h.clamp(0.0, self.height);
+        let closest_radial = distance.min(self.radius);
+
+        let dh = h - closest_h;
+        let dr = distance - closest_radial;
+
+        dh.powi(2) + dr.powi(2) < other.radius().powi(2)
+    }
+}
@@ -0,0 +1,187 @@
+use crate::material::Material;
+use crate::shape::Face;
+use crate::shape::Mesh;
+use crate::shape::Uv;
+use crate::Vec3;
+
+use std::collections::HashMap;
+
+/// Faces at most, refinement stopping before a pass could go over.
+const MAX_FACES: usize = 1 << 22;
+
+/// How finely a mesh is split before being displaced.
+#[derive(Clone, Copy)]
+pub enum Tessellation {
+    /// Every edge split in two that many times, each triangle becoming 4^n.
+    Level(usize),
+    /// Edges split until none is longer than this, finite and positive.
+    EdgeLength(f64),
+}
+
+/// Triangles on their way to a `Mesh`, refined then displaced.
+///
+/// Edges are split at their middle whatever face they belong to, so
+/// neighbouring faces stay connected.
+pub struct Surface {
+    pub positions: Vec<Vec3>,
+    pub uvs: Vec<Uv>,
+    pub faces: Vec<Face>,
+}
+
+/// Index of the middle of `ab` in `values`, shared by both faces of the edge.
+fn middle<T>(
+    a: usize,
+    b: usize,
+    values: &mut Vec<T>,
+    middles: &mut HashMap<(usize, usize), usize>,
+) -> usize
+where
+    T: Copy + std::ops::Add<Output = T> + std::ops::Mul<f64, Output = T>,
+{
+    *middles.entry((a.min(b), a.max(b))).or_insert_with(|| {
+        values.push((values[a] + values[b]) * 0.5);
+        values.len() - 1
+    })
+}
+
+impl Surface {
+    /// Refines the faces, up to `MAX_FACES` of them.
+    pub fn tessellate(&mut self, tessellation: Tessellation) {
+        let passes = match tessellation {
+            Tessellation::Level(level) => level,
+            Tessellation::EdgeLength(length) => {
+                assert!(length.is_finite() && length > 0.0);
+                usize::MAX
+            }
+        };
+
+        for _ in 0..passes {
+            // Each pass at most quadruples the faces
+            if 4 * self.faces.len() > MAX_FACES {
+                break;
+            }
+
+            let split = match tessellation {
+                Tessellation::Level(_) => self.split(|_, _| true),
+                Tessellation::EdgeLength(length) => self.split(|a, b| (a - b).norm() > length),
+            };
+            if !split {
+                break;
+            }
+        }
+    }
+
+    /// Splits the edges `long` picks, returning whether any was.
+    fn split<F>(&mut self, long: F) -> bool
+    where
+        F: Fn(&Vec3, &Vec3) -> bool,
+    {
+        let mut position_middles = HashMap::new();
+        let mut uv_middles = HashMap::new();
+        let mut faces = Vec::with_capacity(self.faces.len());
+
+        for face in &self.faces {
+            let split = [0, 1, 2].map(|k| {
+                let (a, b) = (face.vertices[k], face.vertices[(k + 1) % 3]);
+                long(&self.positions[a], &self.positions[b])
+            });
+
+            // Rotates the corners so that splits start from the first edge
+            let count = split.iter().filter(|&&split| split).count();
+            let start = match count {
+                0 => {
+                    faces.push(*face);
+                    continue;
+                }
+                1 => split.iter().position(|&split| split).unwrap(),
+                2 => (split.iter().position(|&split| !split).unwrap() + 1) % 3,
+                _ => 0,
+            };
+            let rotate = |values: [usize; 3]| [0, 1, 2].map(|k| values[(k + start) % 3]);
+            let vertices = rotate(face.vertices);
+            let uvs = face.uvs.map(rotate);
+
+            // Corners, then the middles of the split edges
+            let mut points = vertices.to_vec();
+            let mut coordinates = uvs.map(|uvs| uvs.to_vec());
+            for k in 0..count {
+                let (a, b) = (k, (k + 1) % 3);
+                points.push(middle(
+                    vertices[a],
+                    vertices[b],
+                    &mut self.positions,
+                    &mut position_middles,
+                ));
+                if let (Some(uvs), Some(coordinates)) = (uvs, &mut coordinates) {
+                    coordinates.push(middle(uvs[a], uvs[b], &mut self.uvs, &mut uv_middles));
+                }
+            }
+
+            let triangles: &[[usize; 3]] = match count {
+                1 => &[[0, 3, 2], [3, 1, 2]],
+                2 => &[[3, 1, 4], [0, 3, 4], [0, 4, 2]],
+                _ => &[[0, 3, 5], [3, 1, 4], [5, 4, 2], [3, 4, 5]],
+            };
+            for triangle in triangles {
+                let vertices = triangle.map(|k| points[k]);
+                let uvs = coordinates.as_ref().map(|uvs| triangle.map(|k| uvs[k]));
+                faces.push(Face::new(vertices, None, uvs, face.material));
+            }
+        }
+
+        let split = faces.len() != self.faces.len();
+        self.faces = faces;
+        split
+    }
+
+    /// Area weighted normals around each position.
+    fn normals(&self) -> Vec<Vec3> {
+        let mut normals = vec![Vec3::zeros(); self.positions.len()];
+        for face in &self.faces {
+            let [a, b, c] = face.vertices.map(|i| &self.positions[i]);
+            let normal = (b - a).cross(&(c - a));
+            for i in face.vertices {
+                normals[i] += normal;
+            }
+        }
+
+        normals
+            .into_iter()
+            .map(|normal| normal.try_normalize(0.0).unwrap_or_else(Vec3::zeros))
+            .collect()
+    }
+
+    /// Moves every position along its normal by `height`, given the material
+    /// and texture coordinates of a face using it, and builds the mesh with
+    /// the normals of the displaced surface.
+    pub fn displace<F>(mut self, height: F, materials: Vec<Box<dyn Material>>) -> Mesh
+    where
+        F: Fn(usize, Option<&Uv>) -> f64,
+    {
+        // Each position takes the height of its first face, keeping
+        // vertices shared by several faces together
+        let mut heights = vec![None; self.positions.len()];
+        for face in &self.faces {
+            for (k, &i) in face.vertices.iter().enumerate() {
+                if heights[i].is_none() {
+                    let uv = face.uvs.map(|uvs| &self.uvs[uvs[k]]);
+                    heights[i] = Some(height(face.material, uv));
+                }
+            }
+        }
+
+        let normals = self.normals();
+        for ((position, normal), height) in self.positions.iter_mut().zip(&normals).zip(heights) {
+            *position += height.unwrap_or(0.0) * normal;
+        }
+
+        let normals = self.normals();
+        let faces = self
+            .faces
+            .into_iter()
+            .map(|face| Face::new(face.vertices, Some(face.vertices), face.uvs, face.material))
+            .collect();
+
+        Mesh::new(self.positions, normals, self.uvs, faces, materials)
+    }
+}
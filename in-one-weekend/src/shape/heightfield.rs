@@ -0,0 +1,244 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::shape::triangle;
+use crate::shape::Uv;
+use crate::Vec3;
+
+/// Grid of heights in `[0, 1]`, row by row along z.
+pub struct Heightmap {
+    pub width: usize,
+    pub depth: usize,
+    pub heights: Vec<f64>,
+}
+
+impl Heightmap {
+    /// Bilinear height at texture coordinates `uv`, repeating the map, `v`
+    /// going up from its last row.
+    pub fn sample(&self, uv: &Uv) -> f64 {
+        let x = uv.x.rem_euclid(1.0) * (self.width - 1) as f64;
+        let y = (1.0 - uv.y.rem_euclid(1.0)) * (self.depth - 1) as f64;
+        let i = (x as usize).min(self.width - 2);
+        let j = (y as usize).min(self.depth - 2);
+        let (s, t) = (x - i as f64, y - j as f64);
+
+        let h = |i: usize, j: usize| self.heights[j * self.width + i];
+        let top = h(i, j) * (1.0 - s) + h(i + 1, j) * s;
+        let bottom = h(i, j + 1) * (1.0 - s) + h(i + 1, j + 1) * s;
+        top * (1.0 - t) + bottom * t
+    }
+}
+
+/// Terrain triangulated from a `Heightmap` stretched over the footprint of
+/// `bounds`, heights 0 and 1 mapping to its bottom and top.
+///
+/// Rays walk the grid cells they cross instead of testing a triangle soup.
+pub struct Heightfield {
+    width: usize,
+    depth: usize,
+    /// World heights.
+    heights: Vec<f64>,
+    /// Per-vertex shading normals.
+    normals: Vec<Vec3>,
+    /// Lowest and highest vertex of each cell.
+    ranges: Vec<(f64, f64)>,
+    origin: Vec3,
+    /// Cell size along x and z.
+    cell: Uv,
+    bounds: Aabb,
+    material: Box<dyn Material>,
+}
+
+impl Heightfield {
+    pub fn new(heightmap: Heightmap, bounds: Aabb, material: Box<dyn Material>) -> Heightfield {
+        let Heightmap {
+            width,
+            depth,
+            heights,
+        } = heightmap;
+        assert!(width >= 2 && depth >= 2);
+        assert_eq!(heights.len(), width * depth);
+
+        let origin = bounds.min;
+        let extent = bounds.extent();
+        let cell = Uv::new(extent.x / (width - 1) as f64, extent.z / (depth - 1) as f64);
+        let heights: Vec<f64> = heights
+            .into_iter()
+            .map(|h| origin.y + h.clamp(0.0, 1.0) * extent.y)
+            .collect();
+
+        let height = |i: usize, j: usize| heights[j * width + i];
+
+        // Central differences, one-sided on the borders
+        let normals = itertools::iproduct!(0..depth, 0..width)
+            .map(|(j, i)| {
+                let (i0, i1) = (i.saturating_sub(1), (i + 1).min(width - 1));
+                let (j0, j1) = (j.saturating_sub(1), (j + 1).min(depth - 1));
+                let dx = (height(i1, j) - height(i0, j)) / ((i1 - i0) as f64 * cell.x);
+                let dz = (height(i, j1) - height(i, j0)) / ((j1 - j0) as f64 * cell.y);
+                Vec3::new(-dx, 1.0, -dz).normalize()
+            })
+            .collect();
+
+        let ranges = itertools::iproduct!(0..depth - 1, 0..width - 1)
+            .map(|(j, i)| {
+                let corners = [
+                    height(i, j),
+                    height(i + 1, j),
+                    height(i, j + 1),
+                    height(i + 1, j + 1),
+                ];
+                let low = corners.iter().copied().fold(f64::INFINITY, f64::min);
+                let high = corners.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                (low, high)
+            })
+            .collect();
+
+        Heightfield {
+            width,
+            depth,
+            heights,
+            normals,
+            ranges,
+            origin,
+            cell,
+            bounds,
+            material,
+        }
+    }
+
+    fn vertex(&self, i: usize, j: usize) -> Vec3 {
+        let x = self.origin.x + i as f64 * self.cell.x;
+        let z = self.origin.z + j as f64 * self.cell.y;
+        Vec3::new(x, self.heights[j * self.width + i], z)
+    }
+
+    /// World height of the terrain above `(x, z)`, clamped to the grid.
+    pub fn height(&self, x: f64, z: f64) -> f64 {
+        let u = ((x - self.origin.x) / self.cell.x).clamp(0.0, (self.width - 1) as f64);
+        let v = ((z - self.origin.z) / self.cell.y).clamp(0.0, (self.depth - 1) as f64);
+        let i = (u as usize).min(self.width - 2);
+        let j = (v as usize).min(self.depth - 2);
+        let (s, t) = (u - i as f64, v - j as f64);
+
+        let h = |i: usize, j: usize| self.heights[j * self.width + i];
+        let near = h(i, j) * (1.0 - s) + h(i + 1, j) * s;
+        let far = h(i, j + 1) * (1.0 - s) + h(i + 1, j + 1) * s;
+        near * (1.0 - t) + far * t
+    }
+
+    /// Hits the two triangles of cell `(i, j)`.
+    fn hit_cell(
+        &self,
+        i: usize,
+        j: usize,
+        min: f64,
+        max: f64,
+        ray: &Ray,
+    ) -> Option<hit::Impact<'_>> {
+        let corners = [(i, j), (i + 1, j + 1), (i + 1, j), (i, j + 1)];
+        let mut closest = None;
+        let mut max = max;
+
+        // Both counter-clockwise seen from above
+        for triangle in [[0, 1, 2], [0, 3, 1]] {
+            let [a, b, c] = triangle.map(|k| corners[k]);
+            let [p, q, r] = [a, b, c].map(|(i, j)| self.vertex(i, j));
+            if let Some(hit) = triangle::intersect(&p, &q, &r, min, max, ray) {
+                max = hit.0;
+                closest = Some(([a, b, c], hit));
+            }
+        }
+
+        let (vertices, (root, barycentric)) = closest?;
+        let normals = vertices.map(|(i, j)| self.normals[j * self.width + i]);
+        let normal = triangle::interpolate(&normals, &barycentric).normalize();
+        let [p, q, r] = vertices.map(|(i, j)| self.vertex(i, j));
+        let point = ray.point_at(root);
+        let material = self.material.as_ref();
+
+        let extent = self.bounds.max - self.origin;
+        let mut impact = hit::Impact::new(root, point, normal, material);
+        // Like the heightmap, v going up from its last row
+        impact.uv = Some(Uv::new(
+            (point.x - self.origin.x) / extent.x,
+            1.0 - (point.z - self.origin.z) / extent.z,
+        ));
+        // Level, the coordinates only depending on x and z
+        impact.derivatives = Some([extent.x * Vec3::x(), -extent.z * Vec3::z()]);
+        impact.curvature = Some(triangle::curvature(&p, &q, &r, &normals));
+        Some(impact)
+    }
+}
+
+impl hit::Hit for Heightfield {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let inverse = ray.direction.map(f64::recip);
+        let (entry, exit) = self.bounds.clip(min, max, ray, &inverse)?;
+
+        // Grid coordinates of the entry point
+        let start = ray.point_at(entry);
+        let u = (start.x - self.origin.x) / self.cell.x;
+        let v = (start.z - self.origin.z) / self.cell.y;
+        let mut i = (u.max(0.0) as usize).min(self.width - 2);
+        let mut j = (v.max(0.0) as usize).min(self.depth - 2);
+
+        // Parameters of the next cell borders, and between two of them
+        let axis = |position: usize, origin: f64, cell: f64, o: f64, d: f64| {
+            if d == 0.0 {
+                return (f64::INFINITY, f64::INFINITY);
+            }
+            let border = position + usize::from(d > 0.0);
+            let next = (origin + border as f64 * cell - o) / d;
+            (next, cell / d.abs())
+        };
+        let (mut next_x, delta_x) =
+            axis(i, self.origin.x, self.cell.x, ray.origin.x, ray.direction.x);
+        let (mut next_z, delta_z) =
+            axis(j, self.origin.z, self.cell.y, ray.origin.z, ray.direction.z);
+
+        let mut enter = entry;
+        loop {
+            let leave = next_x.min(next_z).min(exit);
+
+            // Skips cells the ray passes over or under
+            let (low, high) = self.ranges[j * (self.width - 1) + i];
+            let (y0, y1) = (ray.point_at(enter).y, ray.point_at(leave).y);
+            if y0.min(y1) <= high && y0.max(y1) >= low {
+                if let Some(impact) = self.hit_cell(i, j, min, max, ray) {
+                    return Some(impact);
+                }
+            }
+
+            if leave >= exit {
+                return None;
+            }
+
+            enter = leave;
+            if next_x < next_z {
+                if ray.direction.x > 0.0 {
+                    i += 1;
+                } else {
+                    i = i.checked_sub(1)?;
+                }
+                next_x += delta_x;
+            } else {
+                if ray.direction.z > 0.0 {
+                    j += 1;
+                } else {
+                    j = j.checked_sub(1)?;
+                }
+                next_z += delta_z;
+            }
+
+            if i >= self.width - 1 || j >= self.depth - 1 {
+                return None;
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
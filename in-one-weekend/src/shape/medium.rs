@@ -0,0 +1,52 @@
+use derive_new::new;
+
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::hit::Hit;
+use crate::material::Material;
+use crate::ray::Ray;
+
+/// Homogeneous participating medium, such as smoke or fog, filling the
+/// closed `boundary`.
+///
+/// Rays crossing it scatter at exponentially distributed distances, the
+/// `phase` function picking their new direction.
+#[derive(new)]
+pub struct ConstantMedium<T> {
+    boundary: T,
+    /// Probability of scattering per unit of distance.
+    density: f64,
+    phase: Box<dyn Material>,
+}
+
+impl<T> Hit for ConstantMedium<T>
+where
+    T: Hit,
+{
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        // Rays scattered inside have their entry behind them
+        let entry = self.boundary.hit(f64::NEG_INFINITY, f64::INFINITY, ray)?;
+        let entry = entry.parameter();
+        let exit = self.boundary.hit(entry + 1e-6, f64::INFINITY, ray)?;
+        let (entry, exit) = (entry.max(min), exit.parameter().min(max));
+        if entry >= exit {
+            return None;
+        }
+
+        let speed = ray.direction.norm();
+        let distance = -rand::random::<f64>().ln() / self.density;
+        if distance > (exit - entry) * speed {
+            return None;
+        }
+
+        let root = entry + distance / speed;
+        let point = ray.point_at(root);
+        let normal = -ray.direction / speed;
+        let material = self.phase.as_ref();
+        Some(hit::Impact::new(root, point, normal, material))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
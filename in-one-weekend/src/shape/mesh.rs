@@ -0,0 +1,150 @@
+use derive_new::new;
+
+use crate::aabb::Aabb;
+use crate::bvh::Tree;
+use crate::hit;
+use crate::material::Material;
+use crate::na;
+use crate::ray::Ray;
+use crate::shape::triangle;
+use crate::shape::Intersect;
+use crate::shape::Sphere;
+use crate::Vec3;
+
+/// Texture coordinates.
+pub type Uv = na::Vector2<f64>;
+
+/// Indices of a triangle into the `Mesh` buffers.
+#[derive(Clone, Copy, new)]
+pub struct Face {
+    pub vertices: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+    pub uvs: Option<[usize; 3]>,
+    /// Index into the `Mesh` materials.
+    pub material: usize,
+}
+
+/// Indexed triangle mesh sharing its vertex, normal and UV buffers.
+pub struct Mesh {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<Uv>,
+    /// One per position, if any.
+    colors: Vec<Vec3>,
+    faces: Vec<Face>,
+    materials: Vec<Box<dyn Material>>,
+    tree: Tree,
+}
+
+impl Mesh {
+    pub fn new(
+        positions: Vec<Vec3>,
+        normals: Vec<Vec3>,
+        uvs: Vec<Uv>,
+        faces: Vec<Face>,
+        materials: Vec<Box<dyn Material>>,
+    ) -> Mesh {
+        for face in &faces {
+            assert!(face.vertices.iter().all(|&i| i < positions.len()));
+            assert!(face.normals.iter().flatten().all(|&i| i < normals.len()));
+            assert!(face.uvs.iter().flatten().all(|&i| i < uvs.len()));
+            assert!(face.material < materials.len());
+        }
+
+        let normals = normals
+            .into_iter()
+            .map(|normal| normal.normalize())
+            .collect();
+
+        let bounds: Vec<_> = faces
+            .iter()
+            .map(|face| Aabb::from_points(face.vertices.map(|i| &positions[i])))
+            .collect();
+        let tree = Tree::new(&bounds);
+
+        Mesh {
+            positions,
+            normals,
+            uvs,
+            colors: Vec::new(),
+            faces,
+            materials,
+            tree,
+        }
+    }
+
+    /// Colors the positions, for `VertexColor` textures.
+    pub fn with_colors(mut self, colors: Vec<Vec3>) -> Self {
+        assert_eq!(colors.len(), self.positions.len());
+        self.colors = colors;
+        self
+    }
+
+    fn vertices(&self, face: &Face) -> [&Vec3; 3] {
+        face.vertices.map(|i| &self.positions[i])
+    }
+
+    /// Hits a single `face` of the mesh.
+    pub fn hit_face(&self, face: &Face, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let [a, b, c] = self.vertices(face);
+        let (root, barycentric) = triangle::intersect(a, b, c, min, max, ray)?;
+
+        let (normal, curvature) = match face.normals {
+            Some(indices) => {
+                let normals = indices.map(|i| self.normals[i]);
+                (
+                    triangle::interpolate(&normals, &barycentric).normalize(),
+                    triangle::curvature(a, b, c, &normals),
+                )
+            }
+            None => (triangle::normal(a, b, c), na::Matrix3::zeros()),
+        };
+        let point = ray.point_at(root);
+        let material = self.materials[face.material].as_ref();
+
+        let mut impact = hit::Impact::new(root, point, normal, material);
+        impact.curvature = Some(curvature);
+        if !self.colors.is_empty() {
+            let colors = face.vertices.map(|i| self.colors[i]);
+            impact.color = Some(triangle::interpolate(&colors, &barycentric));
+        }
+        if let Some(indices) = face.uvs {
+            let uvs = indices.map(|i| self.uvs[i]);
+            impact.uv = Some(triangle::interpolate(&uvs, &barycentric));
+
+            // Directions of increasing u and v, from the edges in both spaces
+            let (du1, du2) = (uvs[1] - uvs[0], uvs[2] - uvs[0]);
+            let determinant = du1.x * du2.y - du1.y * du2.x;
+            if determinant != 0.0 {
+                let tangent = (du2.y * (b - a) - du1.y * (c - a)) / determinant;
+                let bitangent = (du1.x * (c - a) - du2.x * (b - a)) / determinant;
+                impact.tangent = tangent.try_normalize(0.0);
+                impact.derivatives = Some([tangent, bitangent]);
+            }
+        }
+
+        Some(impact)
+    }
+}
+
+/// Conservative, testing the bounds of the mesh.
+impl Intersect<Sphere> for Mesh {
+    fn intersect(&self, sphere: &Sphere) -> bool {
+        let bounds = self.tree.bounds();
+        let closest = sphere.center().sup(&bounds.min).inf(&bounds.max);
+
+        (closest - sphere.center()).norm() < sphere.radius()
+    }
+}
+
+impl hit::Hit for Mesh {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        self.tree.hit(min, max, ray, |i, min, max| {
+            self.hit_face(&self.faces[i], min, max, ray)
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.tree.bounds())
+    }
+}
@@ -0,0 +1,64 @@
+use derive_new::new;
+
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::hit::Hit;
+use crate::na;
+use crate::ray::Ray;
+use crate::shape::segment_distance;
+use crate::shape::Intersect;
+use crate::shape::Sphere;
+use crate::Vec3;
+
+/// Sphere sliding along a straight line while the shutter is open.
+pub type MovingSphere = Moving<Sphere>;
+
+/// `Hit` translated by `offset` from time 0 to time 1, staying at either end
+/// outside of that interval.
+#[derive(new)]
+pub struct Moving<T> {
+    object: T,
+    offset: Vec3,
+}
+
+impl<T> Moving<T> {
+    fn offset_at(&self, time: f64) -> Vec3 {
+        time.clamp(0.0, 1.0) * self.offset
+    }
+}
+
+impl Intersect<Sphere> for MovingSphere {
+    fn intersect(&self, other: &Sphere) -> bool {
+        let start = self.object.center();
+        let (distance, _) = segment_distance(start, &(start + self.offset), other.center());
+
+        distance < self.object.radius() + other.radius()
+    }
+}
+
+impl Intersect<MovingSphere> for [Sphere] {
+    fn intersect(&self, other: &MovingSphere) -> bool {
+        self.iter().any(|sphere| other.intersect(sphere))
+    }
+}
+
+impl<T> Hit for Moving<T>
+where
+    T: Hit,
+{
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        // Moves the ray back instead, both sharing the same parameter
+        let offset = self.offset_at(ray.time);
+        let local = ray.transform(&na::convert(na::Translation3::from(-offset)));
+        let mut impact = self.object.hit(min, max, &local)?;
+
+        impact.point += offset;
+        Some(impact)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let start = self.object.bounding_box()?;
+        let end = Aabb::new(start.min + self.offset, start.max + self.offset);
+        Some(start.union(&end))
+    }
+}
@@ -0,0 +1,139 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::na;
+use crate::ray::Ray;
+use crate::shape;
+use crate::shape::Intersect;
+use crate::shape::Sphere;
+use crate::shape::Uv;
+use crate::Vec3;
+
+/// Infinite plane through `point`, facing `normal`.
+///
+/// Texture coordinates are distances from `point` along two axes of the
+/// plane, `u` following the world x axis unless the plane faces it.
+pub struct Plane {
+    point: Vec3,
+    normal: Vec3,
+    axes: [Vec3; 2],
+    material: Box<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Vec3, normal: Vec3, material: Box<dyn Material>) -> Plane {
+        let normal = normal.normalize();
+        let along = if normal.x.abs() < 0.9 {
+            Vec3::x()
+        } else {
+            Vec3::z()
+        };
+        let u = (along - along.dot(&normal) * normal).normalize();
+        let v = normal.cross(&u);
+
+        Plane {
+            point,
+            normal,
+            axes: [u, v],
+            material,
+        }
+    }
+}
+
+/// Parameter at which `ray` crosses the plane through `point` facing `normal`.
+pub fn plane_crossing(point: &Vec3, normal: &Vec3, min: f64, max: f64, ray: &Ray) -> Option<f64> {
+    let denominator = normal.dot(&ray.direction);
+
+    // Parallel to the plane
+    if denominator.abs() < 1e-12 {
+        return None;
+    }
+
+    let root = normal.dot(&(point - ray.origin)) / denominator;
+    (min <= root && root <= max).then_some(root)
+}
+
+impl hit::Hit for Plane {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let root = plane_crossing(&self.point, &self.normal, min, max, ray)?;
+        let point = ray.point_at(root);
+        let material = self.material.as_ref();
+
+        let [u, v] = &self.axes;
+        let offset = point - self.point;
+        let mut impact = hit::Impact::new(root, point, self.normal, material);
+        impact.uv = Some(Uv::new(offset.dot(u), offset.dot(v)));
+        impact.tangent = Some(*u);
+        impact.derivatives = Some([*u, *v]);
+        impact.curvature = Some(na::Matrix3::zeros());
+        Some(impact)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+}
+
+impl Intersect<Sphere> for Plane {
+    fn intersect(&self, other: &Sphere) -> bool {
+        let distance = self.normal.dot(&(other.center() - self.point));
+        distance.abs() < other.radius()
+    }
+}
+
+/// Flat disk of `radius` around `center`, facing `normal`.
+pub struct Disk {
+    center: Vec3,
+    normal: Vec3,
+    radius: f64,
+    material: Box<dyn Material>,
+}
+
+impl Disk {
+    pub fn new(center: Vec3, normal: Vec3, radius: f64, material: Box<dyn Material>) -> Disk {
+        Disk {
+            center,
+            normal: normal.normalize(),
+            radius,
+            material,
+        }
+    }
+}
+
+impl hit::Hit for Disk {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let root = plane_crossing(&self.center, &self.normal, min, max, ray)?;
+        let point = ray.point_at(root);
+        if (point - self.center).norm_squared() > self.radius.powi(2) {
+            return None;
+        }
+
+        let material = self.material.as_ref();
+        let mut impact = hit::Impact::new(root, point, self.normal, material);
+        impact.curvature = Some(na::Matrix3::zeros());
+        Some(impact)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        // Padded when flat
+        let extent = shape::disk_extent(&self.normal, self.radius).add_scalar(1e-6);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
+
+impl Intersect<Sphere> for Disk {
+    fn intersect(&self, other: &Sphere) -> bool {
+        let offset = other.center() - self.center;
+        let height = self.normal.dot(&offset);
+        let planar = offset - height * self.normal;
+
+        // Closest point of the disk to the sphere center
+        let closest = if planar.norm() > self.radius {
+            self.radius * planar.normalize()
+        } else {
+            planar
+        };
+
+        (offset - closest).norm() < other.radius()
+    }
+}
@@ -0,0 +1,112 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::na;
+use crate::ray::Ray;
+use crate::shape;
+use crate::shape::Intersect;
+use crate::shape::Sphere;
+use crate::Vec3;
+
+/// Parallelogram spanned by `u` and `v` from `origin`.
+pub struct Quad {
+    origin: Vec3,
+    u: Vec3,
+    v: Vec3,
+    /// Unit normal, `u × v` being the front.
+    normal: Vec3,
+    /// `u × v` over its squared norm, to get planar coordinates.
+    w: Vec3,
+    material: Box<dyn Material>,
+}
+
+impl Quad {
+    pub fn new(origin: Vec3, u: Vec3, v: Vec3, material: Box<dyn Material>) -> Quad {
+        let n = u.cross(&v);
+
+        Quad {
+            origin,
+            u,
+            v,
+            normal: n.normalize(),
+            w: n / n.norm_squared(),
+            material,
+        }
+    }
+
+    /// Planar coordinates of `point`, in `[0, 1]²` inside the quad.
+    fn coordinates(&self, point: &Vec3) -> (f64, f64) {
+        let p = point - self.origin;
+        let alpha = self.w.dot(&p.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&p));
+        (alpha, beta)
+    }
+
+    /// Closest point of the quad to `point`.
+    fn closest(&self, point: &Vec3) -> Vec3 {
+        let p = point - self.origin;
+        let (alpha, beta) = self.coordinates(point);
+        if (0.0..=1.0).contains(&alpha) && (0.0..=1.0).contains(&beta) {
+            return point - p.dot(&self.normal) * self.normal;
+        }
+
+        // Closest point on the border
+        let corners = [
+            self.origin,
+            self.origin + self.u,
+            self.origin + self.u + self.v,
+            self.origin + self.v,
+        ];
+        (0..4)
+            .map(|i| closest_on_segment(&corners[i], &corners[(i + 1) % 4], point))
+            .min_by(|a, b| {
+                let a = (a - point).norm_squared();
+                let b = (b - point).norm_squared();
+                a.total_cmp(&b)
+            })
+            .unwrap()
+    }
+}
+
+fn closest_on_segment(a: &Vec3, b: &Vec3, point: &Vec3) -> Vec3 {
+    let ab = b - a;
+    let t = (point - a).dot(&ab) / ab.norm_squared();
+    a + t.clamp(0.0, 1.0) * ab
+}
+
+impl hit::Hit for Quad {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let root = shape::plane_crossing(&self.origin, &self.normal, min, max, ray)?;
+        let point = ray.point_at(root);
+        let (alpha, beta) = self.coordinates(&point);
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        let material = self.material.as_ref();
+        let mut impact = hit::Impact::new(root, point, self.normal, material);
+        impact.curvature = Some(na::Matrix3::zeros());
+        Some(impact)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let corners = [
+            self.origin,
+            self.origin + self.u,
+            self.origin + self.v,
+            self.origin + self.u + self.v,
+        ];
+        let aabb = Aabb::from_points(&corners);
+
+        // Pad flat boxes
+        let padding = Vec3::repeat(1e-6);
+        Some(Aabb::new(aabb.min - padding, aabb.max + padding))
+    }
+}
+
+impl Intersect<Sphere> for Quad {
+    fn intersect(&self, other: &Sphere) -> bool {
+        let closest = self.closest(other.center());
+        (closest - other.center()).norm() < other.radius()
+    }
+}
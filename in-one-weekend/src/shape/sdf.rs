@@ -0,0 +1,181 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::Vec3;
+
+use std::sync::Arc;
+
+/// Distance under which the surface is reached.
+const EPSILON: f64 = 1e-4;
+/// Steps taken at most before giving up.
+const MAX_STEPS: usize = 512;
+
+/// Signed distance function, negative inside.
+///
+/// Operators consume the field and return a new one, so they can be chained
+/// like `Transformed`.
+#[derive(Clone)]
+pub struct Field(Arc<dyn Fn(&Vec3) -> f64 + Send + Sync>);
+
+impl Field {
+    /// Field from any distance function, which should not overestimate the
+    /// distance to the surface.
+    pub fn new<F>(distance: F) -> Field
+    where
+        F: Fn(&Vec3) -> f64 + Send + Sync + 'static,
+    {
+        Field(Arc::new(distance))
+    }
+
+    pub fn distance(&self, point: &Vec3) -> f64 {
+        (self.0)(point)
+    }
+
+    pub fn sphere(radius: f64) -> Field {
+        Field::new(move |point| point.norm() - radius)
+    }
+
+    /// Box of half extents `half`.
+    pub fn cuboid(half: Vec3) -> Field {
+        Field::new(move |point| {
+            let q = point.abs() - half;
+            q.sup(&Vec3::zeros()).norm() + q.max().min(0.0)
+        })
+    }
+
+    /// Box of half extents `half` with edges rounded by `radius`.
+    pub fn rounded(half: Vec3, radius: f64) -> Field {
+        let inner = Field::cuboid(half.add_scalar(-radius));
+        Field::new(move |point| inner.distance(point) - radius)
+    }
+
+    /// Ring around the y axis.
+    pub fn torus(major: f64, minor: f64) -> Field {
+        Field::new(move |point| {
+            let radial = point.x.hypot(point.z) - major;
+            radial.hypot(point.y) - minor
+        })
+    }
+
+    pub fn translated(self, offset: Vec3) -> Field {
+        Field::new(move |point| self.distance(&(point - offset)))
+    }
+
+    /// Union blending both surfaces over a distance `k`.
+    pub fn smooth_union(self, other: Field, k: f64) -> Field {
+        Field::new(move |point| {
+            let (a, b) = (self.distance(point), other.distance(point));
+            let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+            b + (a - b) * h - k * h * (1.0 - h)
+        })
+    }
+
+    /// Twists around the y axis by `rate` radians per unit of height.
+    pub fn twist(self, rate: f64) -> Field {
+        Field::new(move |point| {
+            let (sin, cos) = (rate * point.y).sin_cos();
+            let twisted = Vec3::new(
+                cos * point.x - sin * point.z,
+                point.y,
+                sin * point.x + cos * point.z,
+            );
+
+            // Twisting stretches distances away from the axis
+            let stretch = (1.0 + (rate * point.x.hypot(point.z)).powi(2)).sqrt();
+            self.distance(&twisted) / stretch
+        })
+    }
+
+    /// Repeats the cell around the origin with `period`, axes of zero period
+    /// being left alone.
+    pub fn repeat(self, period: Vec3) -> Field {
+        Field::new(move |point| {
+            let cell = point.zip_map(
+                &period,
+                |x, p| {
+                    if p > 0.0 {
+                        x - p * (x / p).round()
+                    } else {
+                        x
+                    }
+                },
+            );
+            self.distance(&cell)
+        })
+    }
+}
+
+/// Surface of a `Field`, rendered by sphere tracing inside `bounds`.
+pub struct Sdf {
+    field: Field,
+    bounds: Aabb,
+    material: Box<dyn Material>,
+}
+
+impl Sdf {
+    pub fn new(field: Field, bounds: Aabb, material: Box<dyn Material>) -> Sdf {
+        Sdf {
+            field,
+            bounds,
+            material,
+        }
+    }
+
+    /// Gradient estimated by central differences on a tetrahedron.
+    fn normal(&self, point: &Vec3) -> Vec3 {
+        [
+            Vec3::new(1.0, -1.0, -1.0),
+            Vec3::new(-1.0, -1.0, 1.0),
+            Vec3::new(-1.0, 1.0, -1.0),
+            Vec3::new(1.0, 1.0, 1.0),
+        ]
+        .iter()
+        .map(|k| k * self.field.distance(&(point + EPSILON * k)))
+        .sum::<Vec3>()
+        .normalize()
+    }
+}
+
+impl hit::Hit for Sdf {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let inverse = ray.direction.map(f64::recip);
+        let (mut root, exit) = self.bounds.clip(min, max, ray, &inverse)?;
+        let speed = ray.direction.norm();
+
+        // Marches on the side of the surface the ray starts from, leaving it
+        // first when starting on it
+        let distance = self.field.distance(&ray.point_at(root));
+        let side = if distance.abs() < EPSILON {
+            ray.direction
+                .dot(&self.normal(&ray.point_at(root)))
+                .signum()
+        } else {
+            distance.signum()
+        };
+        let mut left = distance.abs() >= EPSILON;
+
+        for _ in 0..MAX_STEPS {
+            let distance = side * self.field.distance(&ray.point_at(root));
+            if distance >= EPSILON {
+                left = true;
+            } else if left {
+                let point = ray.point_at(root);
+                let normal = self.normal(&point);
+                let material = self.material.as_ref();
+                return Some(hit::Impact::new(root, point, normal, material));
+            }
+
+            root += distance.max(EPSILON) / speed;
+            if root > exit {
+                break;
+            }
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds)
+    }
+}
@@ -1,11 +1,17 @@
 use derive_new::new;
 
+use crate::aabb::Aabb;
 use crate::hit;
 use crate::material::Material;
+use crate::na;
 use crate::ray::Ray;
 use crate::shape::Intersect;
+use crate::shape::Uv;
 use crate::Vec3;
 
+use std::f64::consts::PI;
+use std::f64::consts::TAU;
+
 #[derive(new)]
 pub struct Sphere {
     center: Vec3,
@@ -14,15 +20,44 @@ pub struct Sphere {
 }
 
 impl Sphere {
-    pub fn stick_to(mut self, other: &Sphere) -> Self {
-        if self.center == other.center {
-            return self;
-        }
+    pub fn center(&self) -> &Vec3 {
+        &self.center
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+impl Sphere {
+    /// Impact at `root`, mapped with longitudes along `u` and latitudes along
+    /// `v` from the south pole.
+    fn impact(&self, root: f64, ray: &Ray) -> hit::Impact<'_> {
+        let point = ray.point_at(root);
+        let normal = (point - self.center) / self.radius;
+        let material = self.material.as_ref();
+
+        // Unit sphere, even for negative radii
+        let outward = normal * self.radius.signum();
+        let phi = (-outward.z).atan2(outward.x) + PI;
+        let theta = (-outward.y).clamp(-1.0, 1.0).acos();
 
-        let direction = (self.center - other.center).normalize();
-        let norm = self.radius + other.radius;
-        self.center = norm * direction + other.center;
-        self
+        let mut impact = hit::Impact::new(root, point, normal, material);
+        impact.curvature = Some(na::Matrix3::identity() / self.radius);
+        impact.uv = Some(Uv::new(phi / TAU, theta / PI));
+        impact.tangent = Vec3::new(outward.z, 0.0, -outward.x).try_normalize(1e-9);
+
+        // Undefined at the poles
+        let ring = outward.xz().norm();
+        if ring > 1e-9 {
+            let radius = self.radius.abs();
+            let (x, y, z) = (outward.x, outward.y, outward.z);
+            impact.derivatives = Some([
+                TAU * radius * Vec3::new(z, 0.0, -x),
+                PI * radius * Vec3::new(-x * y / ring, ring, -y * z / ring),
+            ]);
+        }
+        impact
     }
 }
 
@@ -58,22 +93,21 @@ impl hit::Hit for Sphere {
 
         let root = (-b - sqrt) / a;
         if min <= root && root <= max {
-            let point = ray.point_at(root);
-            let normal = (point - self.center) / self.radius;
-            let material = self.material.as_ref();
-            return Some(hit::Impact::new(root, point, normal, material));
+            return Some(self.impact(root, ray));
         }
 
         let root = (-b + sqrt) / a;
         if min <= root && root <= max {
-            let point = ray.point_at(root);
-            let normal = (point - self.center) / self.radius;
-            let material = self.material.as_ref();
-            return Some(hit::Impact::new(root, point, normal, material));
+            return Some(self.impact(root, ray));
         }
 
         None
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let radius = Vec3::repeat(self.radius.abs());
+        Some(Aabb::new(self.center - radius, self.center + radius))
+    }
 }
 
 pub fn random_in_unit_sphere() -> Vec3 {
@@ -85,3 +119,12 @@ pub fn random_in_unit_sphere() -> Vec3 {
         }
     }
 }
+
+/// Uniformly distributed unit vector.
+pub fn random_on_unit_sphere() -> Vec3 {
+    loop {
+        if let Some(normal) = random_in_unit_sphere().try_normalize(1e-6) {
+            break normal;
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use crate::material::Material;
+use crate::shape::triangle;
+use crate::shape::Face;
+use crate::shape::Mesh;
+use crate::Vec3;
+
+use std::collections::HashMap;
+
+/// Undirected edge, its smallest vertex first.
+type Edge = (usize, usize);
+
+fn edge(a: usize, b: usize) -> Edge {
+    (a.min(b), a.max(b))
+}
+
+/// Polygon control mesh of a Catmull-Clark subdivision surface.
+///
+/// Creased edges have a sharpness, the number of levels they stay sharp
+/// for, fractional values blending smooth and sharp rules. Border edges are
+/// always sharp.
+#[derive(Clone)]
+pub struct Cage {
+    positions: Vec<Vec3>,
+    /// Polygons of at least three vertices, counter-clockwise.
+    faces: Vec<Vec<usize>>,
+    creases: HashMap<Edge, f64>,
+}
+
+impl Cage {
+    pub fn new(positions: Vec<Vec3>, faces: Vec<Vec<usize>>) -> Cage {
+        for face in &faces {
+            assert!(face.len() >= 3);
+            assert!(face.iter().all(|&i| i < positions.len()));
+        }
+
+        Cage {
+            positions,
+            faces,
+            creases: HashMap::new(),
+        }
+    }
+
+    /// Creases the edge `ab`, `f64::INFINITY` keeping it sharp at any level.
+    pub fn with_crease(mut self, a: usize, b: usize, sharpness: f64) -> Self {
+        self.creases.insert(edge(a, b), sharpness);
+        self
+    }
+
+    fn sharpness(&self, edge: &Edge, faces: &[usize]) -> f64 {
+        if faces.len() < 2 {
+            return f64::INFINITY;
+        }
+
+        self.creases.get(edge).copied().unwrap_or(0.0)
+    }
+
+    fn centroid(&self, face: &[usize]) -> Vec3 {
+        face.iter().map(|&i| self.positions[i]).sum::<Vec3>() / face.len() as f64
+    }
+
+    /// One level of subdivision, every face becoming quads.
+    pub fn subdivide(&self) -> Cage {
+        let face_points: Vec<Vec3> = self.faces.iter().map(|face| self.centroid(face)).collect();
+
+        // Faces around each edge, and edges and faces around each vertex
+        let mut edge_faces: HashMap<Edge, Vec<usize>> = HashMap::new();
+        let mut vertex_faces = vec![Vec::new(); self.positions.len()];
+        for (f, face) in self.faces.iter().enumerate() {
+            for (k, &a) in face.iter().enumerate() {
+                let b = face[(k + 1) % face.len()];
+                edge_faces.entry(edge(a, b)).or_default().push(f);
+                vertex_faces[a].push(f);
+            }
+        }
+
+        let mut edges: Vec<Edge> = edge_faces.keys().copied().collect();
+        edges.sort_unstable();
+        let edge_index: HashMap<Edge, usize> =
+            edges.iter().enumerate().map(|(i, &e)| (e, i)).collect();
+
+        let mut vertex_edges = vec![Vec::new(); self.positions.len()];
+        for &(a, b) in &edges {
+            vertex_edges[a].push((a, b));
+            vertex_edges[b].push((a, b));
+        }
+
+        let edge_points: Vec<Vec3> = edges
+            .iter()
+            .map(|e| {
+                let faces = &edge_faces[e];
+                let middle = 0.5 * (self.positions[e.0] + self.positions[e.1]);
+                let sharpness = self.sharpness(e, faces);
+                if sharpness >= 1.0 {
+                    return middle;
+                }
+
+                let smooth = 0.5 * (middle + 0.5 * (face_points[faces[0]] + face_points[faces[1]]));
+                smooth.lerp(&middle, sharpness)
+            })
+            .collect();
+
+        let vertex_points = self.positions.iter().enumerate().map(|(v, position)| {
+            let sharp: Vec<(Edge, f64)> = vertex_edges[v]
+                .iter()
+                .map(|e| (*e, self.sharpness(e, &edge_faces[e])))
+                .filter(|(_, sharpness)| *sharpness > 0.0)
+                .collect();
+
+            let n = vertex_edges[v].len() as f64;
+            let smooth = || {
+                let faces = &vertex_faces[v];
+                let q = faces.iter().map(|&f| face_points[f]).sum::<Vec3>() / faces.len() as f64;
+                let r = vertex_edges[v]
+                    .iter()
+                    .map(|&(a, b)| 0.5 * (self.positions[a] + self.positions[b]))
+                    .sum::<Vec3>()
+                    / n;
+                (q + 2.0 * r + (n - 3.0) * position) / n
+            };
+
+            let sharpened = match sharp.len() {
+                0 | 1 => return smooth(),
+                // Crease: cubic B-spline along the two sharp edges
+                2 => {
+                    let other = |(a, b): Edge| if a == v { b } else { a };
+                    let (a, b) = (other(sharp[0].0), other(sharp[1].0));
+                    (self.positions[a] + 6.0 * position + self.positions[b]) / 8.0
+                }
+                // Corner
+                _ => *position,
+            };
+
+            let sharpness = sharp.iter().map(|(_, s)| s).sum::<f64>() / sharp.len() as f64;
+            if sharpness >= 1.0 {
+                sharpened
+            } else {
+                smooth().lerp(&sharpened, sharpness)
+            }
+        });
+
+        // Vertex points, then edge points, then face points
+        let mut positions: Vec<Vec3> = vertex_points.collect();
+        let edge_offset = positions.len();
+        let face_offset = edge_offset + edge_points.len();
+        positions.extend(edge_points);
+        positions.extend(face_points);
+
+        let edge_point = |a: usize, b: usize| edge_offset + edge_index[&edge(a, b)];
+        let faces = self
+            .faces
+            .iter()
+            .enumerate()
+            .flat_map(|(f, face)| {
+                let m = face.len();
+                (0..m).map(move |k| {
+                    let (previous, v, next) = (face[(k + m - 1) % m], face[k], face[(k + 1) % m]);
+                    vec![
+                        v,
+                        edge_point(v, next),
+                        face_offset + f,
+                        edge_point(previous, v),
+                    ]
+                })
+            })
+            .collect();
+
+        // Each creased edge splits in two, a level less sharp
+        let creases = self
+            .creases
+            .iter()
+            .filter(|(_, &sharpness)| sharpness > 1.0)
+            .flat_map(|(&(a, b), &sharpness)| {
+                let middle = edge_point(a, b);
+                [
+                    (edge(a, middle), sharpness - 1.0),
+                    (edge(middle, b), sharpness - 1.0),
+                ]
+            })
+            .collect();
+
+        Cage {
+            positions,
+            faces,
+            creases,
+        }
+    }
+
+    /// Subdivides `level` times and triangulates the limit approximation.
+    ///
+    /// Normals are averaged around each vertex, separately on each side of
+    /// the creases that are still sharp and of the borders.
+    pub fn tessellate(&self, level: usize, material: Box<dyn Material>) -> Mesh {
+        let cage = (0..level).fold(self.clone(), |cage, _| cage.subdivide());
+
+        let triangles: Vec<[usize; 3]> = cage
+            .faces
+            .iter()
+            .flat_map(|face| (1..face.len() - 1).map(move |k| [face[0], face[k], face[k + 1]]))
+            .filter(|&[a, b, c]| {
+                // Degenerate triangles of collapsed cages
+                let [p, q, r] = [a, b, c].map(|i| &cage.positions[i]);
+                triangle::normal(p, q, r).iter().all(|x| x.is_finite())
+            })
+            .collect();
+
+        // Triangle corners, `3 t + k`, joined across smooth edges
+        let mut corners = Corners::new(3 * triangles.len());
+        let mut edge_corners: HashMap<Edge, Vec<(usize, usize)>> = HashMap::new();
+        for (t, triangle) in triangles.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (3 * t + k, 3 * t + (k + 1) % 3);
+                let (v, w) = (triangle[k], triangle[(k + 1) % 3]);
+                // Ordered by vertex, matching the corners of both sides
+                let pair = if v < w { (a, b) } else { (b, a) };
+                edge_corners.entry(edge(v, w)).or_default().push(pair);
+            }
+        }
+        for (e, pairs) in &edge_corners {
+            let sharp = cage
+                .creases
+                .get(e)
+                .is_some_and(|&sharpness| sharpness > 0.0);
+            if let ([(a, b), (c, d)], false) = (&pairs[..], sharp) {
+                corners.join(*a, *c);
+                corners.join(*b, *d);
+            }
+        }
+
+        // One area weighted normal per group of joined corners
+        let mut groups = HashMap::new();
+        let mut normals = Vec::new();
+        let mut indices = vec![0; 3 * triangles.len()];
+        for (t, &[a, b, c]) in triangles.iter().enumerate() {
+            let [p, q, r] = [a, b, c].map(|i| &cage.positions[i]);
+            let normal = (q - p).cross(&(r - p));
+            for (k, group) in indices[3 * t..3 * t + 3].iter_mut().enumerate() {
+                let index = *groups.entry(corners.root(3 * t + k)).or_insert_with(|| {
+                    normals.push(Vec3::zeros());
+                    normals.len() - 1
+                });
+                normals[index] += normal;
+                *group = index;
+            }
+        }
+
+        let faces = triangles
+            .into_iter()
+            .enumerate()
+            .map(|(t, vertices)| {
+                let normals = [0, 1, 2].map(|k| indices[3 * t + k]);
+                Face::new(vertices, Some(normals), None, 0)
+            })
+            .collect();
+
+        Mesh::new(cage.positions, normals, Vec::new(), faces, vec![material])
+    }
+}
+
+/// Disjoint sets of triangle corners.
+struct Corners {
+    parents: Vec<usize>,
+}
+
+impl Corners {
+    fn new(count: usize) -> Corners {
+        Corners {
+            parents: (0..count).collect(),
+        }
+    }
+
+    fn root(&mut self, mut corner: usize) -> usize {
+        while self.parents[corner] != corner {
+            self.parents[corner] = self.parents[self.parents[corner]];
+            corner = self.parents[corner];
+        }
+        corner
+    }
+
+    fn join(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.root(a), self.root(b));
+        self.parents[a] = b;
+    }
+}
@@ -0,0 +1,116 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::polynomial;
+use crate::ray::Ray;
+use crate::shape;
+use crate::shape::Intersect;
+use crate::shape::Sphere;
+use crate::Vec3;
+
+/// Ring of tube radius `minor` swept around the circle of radius `major`,
+/// centered on `center` and facing the unit `axis`.
+pub struct Torus {
+    center: Vec3,
+    axis: Vec3,
+    major: f64,
+    minor: f64,
+    material: Box<dyn Material>,
+}
+
+impl Torus {
+    pub fn new(
+        center: Vec3,
+        axis: Vec3,
+        major: f64,
+        minor: f64,
+        material: Box<dyn Material>,
+    ) -> Torus {
+        Torus {
+            center,
+            axis: axis.normalize(),
+            major,
+            minor,
+            material,
+        }
+    }
+
+    /// Implicit function `(|p|² + R² - r²)² - 4 R² (|p|² - (p·axis)²)` along
+    /// `origin + t direction`, as a quartic in `t`.
+    fn coefficients(&self, origin: &Vec3, direction: &Vec3) -> [f64; 5] {
+        let major2 = self.major.powi(2);
+
+        let s = direction.norm_squared();
+        let u = origin.dot(direction);
+        let v = origin.norm_squared() + major2 - self.minor.powi(2);
+
+        // Squared distance to the axis: a t² + 2 b t + c
+        let (oa, da) = (origin.dot(&self.axis), direction.dot(&self.axis));
+        let a = s - da.powi(2);
+        let b = u - oa * da;
+        let c = origin.norm_squared() - oa.powi(2);
+
+        [
+            s.powi(2),
+            4.0 * s * u,
+            2.0 * s * v + 4.0 * u.powi(2) - 4.0 * major2 * a,
+            4.0 * u * v - 8.0 * major2 * b,
+            v.powi(2) - 4.0 * major2 * c,
+        ]
+    }
+
+    /// Outward normal at `local`, relative to the center.
+    fn normal(&self, local: &Vec3) -> Vec3 {
+        let radial = local - local.dot(&self.axis) * self.axis;
+        let v = local.norm_squared() + self.major.powi(2) - self.minor.powi(2);
+
+        (v * local - 2.0 * self.major.powi(2) * radial).normalize()
+    }
+}
+
+impl Intersect<Sphere> for Torus {
+    fn intersect(&self, sphere: &Sphere) -> bool {
+        let local = sphere.center() - self.center;
+        let height = local.dot(&self.axis);
+        let radial = (local - height * self.axis).norm();
+        let distance = (radial - self.major).hypot(height);
+
+        distance < self.minor + sphere.radius()
+    }
+}
+
+impl hit::Hit for Torus {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let centered = ray.origin - self.center;
+
+        // Starting from the bounding sphere keeps the coefficients small
+        let a = ray.direction.norm_squared();
+        let b = 2.0 * ray.direction.dot(&centered);
+        let c = centered.norm_squared() - (self.major + self.minor).powi(2);
+        let roots = polynomial::quadratic(a, b, c);
+        let (&r0, &r1) = (roots.first()?, roots.last()?);
+        if r1 < min || max < r0 {
+            return None;
+        }
+
+        let shift = r0.max(min);
+        let origin = centered + shift * ray.direction;
+        let [c4, c3, c2, c1, c0] = self.coefficients(&origin, &ray.direction);
+
+        let root = polynomial::quartic(c4, c3, c2, c1, c0)
+            .into_iter()
+            .map(|root| root + shift)
+            .find(|root| min <= *root && *root <= max)?;
+
+        let point = ray.point_at(root);
+        let normal = self.normal(&(point - self.center));
+        let material = self.material.as_ref();
+
+        Some(hit::Impact::new(root, point, normal, material))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = shape::disk_extent(&self.axis, self.major) + Vec3::repeat(self.minor);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
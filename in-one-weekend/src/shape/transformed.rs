@@ -0,0 +1,112 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::hit::Hit;
+use crate::na;
+use crate::ray::Ray;
+use crate::Vec3;
+
+use std::sync::Arc;
+
+/// Shared `Hit` placed in the world by a transform.
+///
+/// The object is never copied, so the same geometry can be instanced many
+/// times.
+pub type Instance = Transformed<Arc<dyn Hit>>;
+
+/// `Hit` moved by an affine transform, non-uniform scale included.
+pub struct Transformed<T> {
+    object: T,
+    /// Object to world.
+    transform: na::Affine3<f64>,
+    /// World to object.
+    inverse: na::Affine3<f64>,
+    /// Object to world, for normals.
+    normal: na::Matrix3<f64>,
+}
+
+impl<T> Transformed<T> {
+    /// `transform` is any nalgebra transform with a homogeneous matrix, such
+    /// as an `Affine3`, an `Isometry3` or a `Translation3`. It must be
+    /// invertible, without any zero scale.
+    pub fn new<M>(object: T, transform: M) -> Transformed<T>
+    where
+        M: Into<na::Matrix4<f64>>,
+    {
+        let transform = na::Affine3::from_matrix_unchecked(transform.into());
+        let inverse = transform.try_inverse().expect("singular transform");
+        let normal = inverse.matrix().fixed_slice::<3, 3>(0, 0).transpose();
+
+        Transformed {
+            object,
+            transform,
+            inverse,
+            normal,
+        }
+    }
+
+    /// Applies `transform` after the current one.
+    pub fn then<M>(self, transform: M) -> Transformed<T>
+    where
+        M: Into<na::Matrix4<f64>>,
+    {
+        let matrix = transform.into() * self.transform.matrix();
+        Transformed::new(self.object, matrix)
+    }
+
+    pub fn translated(self, offset: Vec3) -> Transformed<T> {
+        self.then(na::Translation3::from(offset))
+    }
+
+    /// Rotates by `axis_angle`, the rotation axis scaled by its angle in
+    /// radians.
+    pub fn rotated(self, axis_angle: Vec3) -> Transformed<T> {
+        self.then(na::Rotation3::new(axis_angle))
+    }
+
+    pub fn scaled(self, scale: Vec3) -> Transformed<T> {
+        self.then(na::Matrix4::new_nonuniform_scaling(&scale))
+    }
+}
+
+impl<T> Hit for Transformed<T>
+where
+    T: Hit,
+{
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        // Both rays share the same parameter
+        let local = ray.transform(&self.inverse);
+        let mut impact = self.object.hit(min, max, &local)?;
+
+        impact.point = ray.point_at(impact.parameter());
+        let normal = self.normal * impact.normal;
+        impact.normal = normal.normalize();
+        // From world offsets to the turning of the world normal
+        impact.curvature = impact.curvature.map(|curvature| {
+            let inverse = self.inverse.matrix().fixed_slice::<3, 3>(0, 0);
+            let across = na::Matrix3::identity() - impact.normal * impact.normal.transpose();
+            across * self.normal * curvature * inverse / normal.norm()
+        });
+        impact.tangent = impact
+            .tangent
+            .map(|tangent| self.transform.transform_vector(&tangent).normalize());
+        impact.derivatives = impact
+            .derivatives
+            .map(|derivatives| derivatives.map(|d| self.transform.transform_vector(&d)));
+
+        Some(impact)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let local = self.object.bounding_box()?;
+        let corners = (0..8).map(|i| {
+            let corner = Vec3::new(
+                if i & 1 == 0 { local.min.x } else { local.max.x },
+                if i & 2 == 0 { local.min.y } else { local.max.y },
+                if i & 4 == 0 { local.min.z } else { local.max.z },
+            );
+            self.transform.transform_point(&corner.into()).coords
+        });
+
+        Some(corners.fold(Aabb::empty(), |aabb, corner| aabb.grow(&corner)))
+    }
+}
@@ -0,0 +1,124 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::na;
+use crate::ray::Ray;
+use crate::Vec3;
+
+pub struct Triangle {
+    vertices: [Vec3; 3],
+    /// Per-vertex shading normals.
+    normals: Option<[Vec3; 3]>,
+    material: Box<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(vertices: [Vec3; 3], material: Box<dyn Material>) -> Triangle {
+        Triangle {
+            vertices,
+            normals: None,
+            material,
+        }
+    }
+
+    pub fn with_normals(mut self, normals: [Vec3; 3]) -> Self {
+        self.normals = Some(normals.map(|normal| normal.normalize()));
+        self
+    }
+}
+
+impl hit::Hit for Triangle {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let [a, b, c] = &self.vertices;
+        let (root, barycentric) = intersect(a, b, c, min, max, ray)?;
+
+        let (normal, curvature) = match &self.normals {
+            Some(normals) => (
+                interpolate(normals, &barycentric).normalize(),
+                curvature(a, b, c, normals),
+            ),
+            None => (normal(a, b, c), na::Matrix3::zeros()),
+        };
+        let point = ray.point_at(root);
+        let material = self.material.as_ref();
+
+        let mut impact = hit::Impact::new(root, point, normal, material);
+        impact.curvature = Some(curvature);
+        Some(impact)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::from_points(&self.vertices))
+    }
+}
+
+/// Geometric normal of the triangle `abc`, counter-clockwise being the front.
+pub fn normal(a: &Vec3, b: &Vec3, c: &Vec3) -> Vec3 {
+    (b - a).cross(&(c - a)).normalize()
+}
+
+/// How `normals` interpolated over the triangle `abc` turn as the point
+/// moves, `dn/dp`.
+pub fn curvature(a: &Vec3, b: &Vec3, c: &Vec3, normals: &[Vec3; 3]) -> na::Matrix3<f64> {
+    let edges = na::Matrix3::from_columns(&[b - a, c - a, normal(a, b, c)]);
+    let turns = na::Matrix3::from_columns(&[
+        normals[1] - normals[0],
+        normals[2] - normals[0],
+        Vec3::zeros(),
+    ]);
+
+    edges
+        .try_inverse()
+        .map_or_else(na::Matrix3::zeros, |inverse| turns * inverse)
+}
+
+/// Interpolates per-vertex `values` with `barycentric` coordinates.
+pub fn interpolate<T>(values: &[T; 3], barycentric: &Vec3) -> T
+where
+    T: Copy + std::ops::Mul<f64, Output = T> + std::ops::Add<Output = T>,
+{
+    values[0] * barycentric.x + values[1] * barycentric.y + values[2] * barycentric.z
+}
+
+/// Möller–Trumbore ray/triangle intersection.
+///
+/// Returns the ray parameter and the barycentric coordinates of the impact
+/// relative to `a`, `b` and `c`.
+pub fn intersect(
+    a: &Vec3,
+    b: &Vec3,
+    c: &Vec3,
+    min: f64,
+    max: f64,
+    ray: &Ray,
+) -> Option<(f64, Vec3)> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = ray.direction.cross(&edge2);
+    let determinant = edge1.dot(&p);
+
+    // Parallel to the triangle plane
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+
+    let inverse = determinant.recip();
+    let s = ray.origin - a;
+    let u = inverse * s.dot(&p);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = s.cross(&edge1);
+    let v = inverse * ray.direction.dot(&q);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let root = inverse * edge2.dot(&q);
+    if root < min || max < root {
+        return None;
+    }
+
+    Some((root, Vec3::new(1.0 - u - v, u, v)))
+}
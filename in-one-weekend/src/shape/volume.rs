@@ -0,0 +1,185 @@
+use crate::aabb::Aabb;
+use crate::hit;
+use crate::material::Material;
+use crate::material::Scattered;
+use crate::ray::Ray;
+use crate::Vec3;
+
+/// Dense voxel grid of densities, with temperatures in kelvins for fire,
+/// `x` varying fastest then `y`.
+pub struct Grid {
+    pub size: [usize; 3],
+    pub densities: Vec<f64>,
+    pub temperatures: Option<Vec<f64>>,
+}
+
+impl Grid {
+    /// Trilinear interpolation of voxel centered `values` at `point` of the
+    /// unit cube.
+    fn lookup(&self, values: &[f64], point: &Vec3) -> f64 {
+        let [width, height, _] = self.size;
+        let voxel = |i: usize, j: usize, k: usize| values[(k * height + j) * width + i];
+
+        let mut corners = [(0, 0, 0.0); 3];
+        for (axis, corner) in corners.iter_mut().enumerate() {
+            let size = self.size[axis];
+            let x = (point[axis] * size as f64 - 0.5).clamp(0.0, (size - 1) as f64);
+            let i = (x as usize).min(size.saturating_sub(2));
+            *corner = (i, (i + 1).min(size - 1), x - i as f64);
+        }
+
+        let [(i0, i1, s), (j0, j1, t), (k0, k1, u)] = corners;
+        let lerp = |a: f64, b: f64, t: f64| a + t * (b - a);
+        let near = lerp(
+            lerp(voxel(i0, j0, k0), voxel(i1, j0, k0), s),
+            lerp(voxel(i0, j1, k0), voxel(i1, j1, k0), s),
+            t,
+        );
+        let far = lerp(
+            lerp(voxel(i0, j0, k1), voxel(i1, j0, k1), s),
+            lerp(voxel(i0, j1, k1), voxel(i1, j1, k1), s),
+            t,
+        );
+        lerp(near, far, u)
+    }
+
+    pub fn density(&self, point: &Vec3) -> f64 {
+        self.lookup(&self.densities, point)
+    }
+
+    pub fn temperature(&self, point: &Vec3) -> Option<f64> {
+        let temperatures = self.temperatures.as_ref()?;
+        Some(self.lookup(temperatures, point))
+    }
+}
+
+/// Scattering, and glowing, part of a `Volume`.
+struct Medium {
+    grid: Grid,
+    bounds: Aabb,
+    /// Density scale.
+    density: f64,
+    /// Highest density, bounding those in between voxels too.
+    majorant: f64,
+    phase: Box<dyn Material>,
+    /// Blackbody emission scale.
+    emission: f64,
+}
+
+impl Medium {
+    /// Unit cube coordinates of the world `point`.
+    fn local(&self, point: &Vec3) -> Vec3 {
+        (point - self.bounds.min).component_div(&self.bounds.extent())
+    }
+}
+
+impl Material for Medium {
+    fn scatter(&self, ray: Ray, impact: &hit::Impact<'_>) -> Option<Scattered> {
+        self.phase.scatter(ray, impact)
+    }
+
+    fn emitted(&self, impact: &hit::Impact<'_>) -> Vec3 {
+        if self.emission == 0.0 {
+            return Vec3::zeros();
+        }
+
+        let temperature = self.grid.temperature(&self.local(&impact.point));
+        temperature.map_or_else(Vec3::zeros, |kelvins| self.emission * blackbody(kelvins))
+    }
+}
+
+/// Heterogeneous participating medium, such as a cloud or an explosion, its
+/// densities given by a `Grid` stretched over `bounds`.
+///
+/// Rays are delta tracked: tentative collisions are drawn against the
+/// highest density, each being real with the density ratio there.
+pub struct Volume {
+    medium: Medium,
+}
+
+impl Volume {
+    /// Densities must be finite and non-negative, sampling stalling otherwise.
+    pub fn new(grid: Grid, bounds: Aabb, density: f64, phase: Box<dyn Material>) -> Volume {
+        assert!(grid
+            .densities
+            .iter()
+            .all(|value| value.is_finite() && *value >= 0.0));
+        let majorant = density * grid.densities.iter().copied().fold(0.0, f64::max);
+        assert!(majorant.is_finite() && majorant >= 0.0);
+
+        Volume {
+            medium: Medium {
+                grid,
+                bounds,
+                density,
+                majorant,
+                phase,
+                emission: 0.0,
+            },
+        }
+    }
+
+    /// Glows as a blackbody at the grid temperatures, scaled by `emission`,
+    /// unit emission being as bright as the peak of a blackbody at 3000 K.
+    pub fn with_emission(mut self, emission: f64) -> Self {
+        self.medium.emission = emission;
+        self
+    }
+}
+
+impl hit::Hit for Volume {
+    fn hit(&self, min: f64, max: f64, ray: &Ray) -> Option<hit::Impact<'_>> {
+        let medium = &self.medium;
+        if medium.majorant <= 0.0 {
+            return None;
+        }
+
+        let inverse = ray.direction.map(f64::recip);
+        let (entry, exit) = medium.bounds.clip(min, max, ray, &inverse)?;
+
+        let speed = ray.direction.norm();
+        let mut root = entry;
+        loop {
+            root -= (1.0 - rand::random::<f64>()).ln() / (medium.majorant * speed);
+            if root >= exit {
+                return None;
+            }
+
+            let point = ray.point_at(root);
+            let density = medium.density * medium.grid.density(&medium.local(&point));
+            if rand::random::<f64>() * medium.majorant < density {
+                let normal = -ray.direction / speed;
+                return Some(hit::Impact::new(root, point, normal, medium));
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.medium.bounds)
+    }
+}
+
+/// Color of a blackbody at `kelvins`, normalized by the peak emission at
+/// `REFERENCE` so that hotter voxels also glow brighter.
+fn blackbody(kelvins: f64) -> Vec3 {
+    const C: f64 = 299_792_458.0;
+    const H: f64 = 6.626_070_15e-34;
+    const KB: f64 = 1.380_649e-23;
+    /// Wien's displacement constant, in meter kelvins.
+    const WIEN: f64 = 2.897_771_955e-3;
+    /// Temperature glowing at unit intensity, in kelvins.
+    const REFERENCE: f64 = 3000.0;
+
+    if kelvins <= 0.0 {
+        return Vec3::zeros();
+    }
+
+    let planck = |wavelength: f64, kelvins: f64| {
+        2.0 * H * C.powi(2)
+            / (wavelength.powi(5) * ((H * C / (wavelength * KB * kelvins)).exp() - 1.0))
+    };
+
+    // Red, green and blue wavelengths
+    let peak = planck(WIEN / REFERENCE, REFERENCE);
+    Vec3::new(610e-9, 550e-9, 465e-9).map(|wavelength| planck(wavelength, kelvins)) / peak
+}
@@ -0,0 +1,68 @@
+use crate::hit;
+use crate::Vec3;
+
+use std::sync::Arc;
+
+mod bitmap;
+mod checker;
+mod noise;
+mod vertex;
+
+pub use crate::texture::bitmap::*;
+pub use crate::texture::checker::*;
+pub use crate::texture::noise::*;
+pub use crate::texture::vertex::*;
+
+/// Color varying over surfaces, looked up at the texture coordinates and
+/// world position of an `Impact`.
+pub trait Texture: Send + Sync {
+    fn boxed(self) -> Box<dyn Texture>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+
+    fn value(&self, impact: &hit::Impact<'_>) -> Vec3;
+
+    /// Gray level, for textures driving scalar parameters.
+    fn scalar(&self, impact: &hit::Impact<'_>) -> f64 {
+        self.value(impact).mean()
+    }
+}
+
+/// Constant scalar.
+impl Texture for f64 {
+    fn value(&self, _: &hit::Impact<'_>) -> Vec3 {
+        Vec3::repeat(*self)
+    }
+
+    fn scalar(&self, _: &hit::Impact<'_>) -> f64 {
+        *self
+    }
+}
+
+/// Constant color.
+impl Texture for Vec3 {
+    fn value(&self, _: &hit::Impact<'_>) -> Vec3 {
+        *self
+    }
+}
+
+impl<T> Texture for Box<T>
+where
+    T: Texture + ?Sized,
+{
+    fn value(&self, impact: &hit::Impact<'_>) -> Vec3 {
+        (**self).value(impact)
+    }
+}
+
+impl<T> Texture for Arc<T>
+where
+    T: Texture + ?Sized,
+{
+    fn value(&self, impact: &hit::Impact<'_>) -> Vec3 {
+        (**self).value(impact)
+    }
+}
@@ -0,0 +1,211 @@
+use crate::hit;
+use crate::shape::Uv;
+use crate::texture::Texture;
+use crate::Vec3;
+
+/// How texel indices beyond the image borders are brought back in.
+#[derive(Clone, Copy)]
+pub enum Wrap {
+    Repeat,
+    /// Stretches the border texels.
+    Clamp,
+    /// Repeats the image flipped every other time.
+    Mirror,
+}
+
+impl Wrap {
+    fn index(self, i: i64, size: usize) -> usize {
+        let size = size as i64;
+        let i = match self {
+            Wrap::Repeat => i.rem_euclid(size),
+            Wrap::Clamp => i.clamp(0, size - 1),
+            Wrap::Mirror => {
+                let i = i.rem_euclid(2 * size);
+                if i < size {
+                    i
+                } else {
+                    2 * size - 1 - i
+                }
+            }
+        };
+        i as usize
+    }
+}
+
+/// How texels are combined between their centers.
+#[derive(Clone, Copy)]
+pub enum Filter {
+    Nearest,
+    /// Weighs the four closest texels.
+    Bilinear,
+    /// Blends bilinear lookups in the two MIP levels whose texels are about
+    /// the size of the pixel footprint.
+    Trilinear,
+}
+
+/// Image, or one of its halvings.
+struct Level {
+    width: usize,
+    height: usize,
+    /// Row by row, from the top.
+    texels: Vec<Vec3>,
+}
+
+impl Level {
+    /// Averages blocks of 2×2 texels, duplicating the last row or column of
+    /// odd sizes.
+    fn halve(&self) -> Level {
+        let width = self.width.div_ceil(2);
+        let height = self.height.div_ceil(2);
+        let texel = |i: usize, j: usize| {
+            self.texels[j.min(self.height - 1) * self.width + i.min(self.width - 1)]
+        };
+
+        let texels = itertools::iproduct!(0..height, 0..width)
+            .map(|(j, i)| {
+                let [x, y] = [2 * i, 2 * j];
+                0.25 * (texel(x, y) + texel(x + 1, y) + texel(x, y + 1) + texel(x + 1, y + 1))
+            })
+            .collect();
+
+        Level {
+            width,
+            height,
+            texels,
+        }
+    }
+}
+
+/// Image of linear colors mapped over the texture coordinates, `v` going up
+/// from its last row.
+///
+/// Surfaces without coordinates see its bottom left corner.
+pub struct Bitmap {
+    /// MIP pyramid, from the full image down to a single texel.
+    levels: Vec<Level>,
+    /// Along `u` and `v`.
+    wrap: [Wrap; 2],
+    filter: Filter,
+    /// Repetitions of the image per unit of texture coordinates.
+    scale: f64,
+}
+
+impl Bitmap {
+    /// Repeating and trilinearly filtered.
+    pub fn new(width: usize, height: usize, texels: Vec<Vec3>) -> Bitmap {
+        assert!(width > 0 && height > 0);
+        assert_eq!(texels.len(), width * height);
+
+        let mut levels = vec![Level {
+            width,
+            height,
+            texels,
+        }];
+        while let Some(level) = levels.last().filter(|level| level.width * level.height > 1) {
+            levels.push(level.halve());
+        }
+
+        Bitmap {
+            levels,
+            wrap: [Wrap::Repeat; 2],
+            filter: Filter::Trilinear,
+            scale: 1.0,
+        }
+    }
+
+    pub fn with_wrap(mut self, u: Wrap, v: Wrap) -> Self {
+        self.wrap = [u, v];
+        self
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    fn texel(&self, level: usize, i: i64, j: i64) -> Vec3 {
+        let level = &self.levels[level];
+        let i = self.wrap[0].index(i, level.width);
+        let j = self.wrap[1].index(j, level.height);
+        level.texels[j * level.width + i]
+    }
+
+    /// Texel space of `level`, from its top left corner.
+    fn locate(&self, level: usize, uv: &Uv) -> (f64, f64) {
+        let level = &self.levels[level];
+        let x = self.scale * uv.x * level.width as f64;
+        let y = (1.0 - self.scale * uv.y) * level.height as f64;
+        (x, y)
+    }
+
+    fn bilinear(&self, level: usize, uv: &Uv) -> Vec3 {
+        let (x, y) = self.locate(level, uv);
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (i, j) = (x.floor(), y.floor());
+        let (s, t) = (x - i, y - j);
+        let (i, j) = (i as i64, j as i64);
+
+        let top = self
+            .texel(level, i, j)
+            .lerp(&self.texel(level, i + 1, j), s);
+        let bottom = self
+            .texel(level, i, j + 1)
+            .lerp(&self.texel(level, i + 1, j + 1), s);
+        top.lerp(&bottom, t)
+    }
+
+    /// Fractional MIP level whose texels span the widest side of `footprint`.
+    fn level(&self, footprint: &[Uv; 2]) -> f64 {
+        let image = &self.levels[0];
+        let texels = self.scale * Uv::new(image.width as f64, image.height as f64);
+        let width = footprint
+            .iter()
+            .map(|offset| offset.component_mul(&texels).norm())
+            .fold(0.0, f64::max);
+
+        let top = (self.levels.len() - 1) as f64;
+        width.log2().clamp(0.0, top)
+    }
+
+    fn sample(&self, uv: &Uv, footprint: Option<&[Uv; 2]>) -> Vec3 {
+        match self.filter {
+            Filter::Nearest => {
+                let (x, y) = self.locate(0, uv);
+                self.texel(0, x.floor() as i64, y.floor() as i64)
+            }
+            Filter::Bilinear => self.bilinear(0, uv),
+            Filter::Trilinear => {
+                // Full resolution when seen indirectly
+                let level = footprint.map_or(0.0, |footprint| self.level(footprint));
+                let (fine, blend) = (level.floor() as usize, level.fract());
+                if blend == 0.0 {
+                    return self.bilinear(fine, uv);
+                }
+
+                self.bilinear(fine, uv)
+                    .lerp(&self.bilinear(fine + 1, uv), blend)
+            }
+        }
+    }
+}
+
+impl Texture for Bitmap {
+    fn value(&self, impact: &hit::Impact<'_>) -> Vec3 {
+        let uv = impact.uv.unwrap_or_else(Uv::zeros);
+        self.sample(&uv, impact.footprint.as_ref())
+    }
+}
+
+/// Decodes a color channel in `[0, 1]` stored with the sRGB transfer curve.
+pub fn srgb_to_linear(value: f64) -> f64 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
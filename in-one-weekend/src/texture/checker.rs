@@ -0,0 +1,37 @@
+use crate::hit;
+use crate::texture::Texture;
+use crate::Vec3;
+
+/// Alternates two textures in squares of `size` over the texture
+/// coordinates, or in cubes of `size` centered on the world grid on surfaces
+/// without any.
+pub struct Checker {
+    even: Box<dyn Texture>,
+    odd: Box<dyn Texture>,
+    size: f64,
+}
+
+impl Checker {
+    pub fn new(even: impl Texture + 'static, odd: impl Texture + 'static, size: f64) -> Checker {
+        Checker {
+            even: even.boxed(),
+            odd: odd.boxed(),
+            size,
+        }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, impact: &hit::Impact<'_>) -> Vec3 {
+        let cells = match &impact.uv {
+            Some(uv) => (uv / self.size).map(f64::floor).sum(),
+            None => (impact.point / self.size).map(|x| (x + 0.5).floor()).sum(),
+        };
+
+        if cells.rem_euclid(2.0) < 1.0 {
+            self.even.value(impact)
+        } else {
+            self.odd.value(impact)
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::hit;
+use crate::texture::Texture;
+use crate::Vec3;
+
+use std::f64::consts::TAU;
+
+/// Size of the lattice before it repeats.
+const PERIOD: usize = 256;
+
+/// Gradient noise over a lattice of random unit vectors, in about `[-1, 1]`.
+pub struct Perlin {
+    gradients: Vec<Vec3>,
+    /// Shuffled indices along each axis.
+    permutations: [Vec<usize>; 3],
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Perlin {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let gradients = (0..PERIOD)
+            .map(|_| loop {
+                let random = Vec3::from_fn(|_, _| rng.gen_range(-1.0..1.0));
+                let norm = random.norm();
+                if 1e-3 < norm && norm <= 1.0 {
+                    break random / norm;
+                }
+            })
+            .collect();
+
+        let permutations = [(); 3].map(|_| {
+            let mut permutation: Vec<usize> = (0..PERIOD).collect();
+            permutation.shuffle(&mut rng);
+            permutation
+        });
+
+        Perlin {
+            gradients,
+            permutations,
+        }
+    }
+
+    pub fn noise(&self, point: &Vec3) -> f64 {
+        let cell = point.map(f64::floor);
+        let offset = point - cell;
+        // Hermite smoothing of the weights
+        let smooth = offset.map(|t| t * t * (3.0 - 2.0 * t));
+
+        let [x, y, z] = [cell.x, cell.y, cell.z].map(|c| c as i64);
+        let index =
+            |axis: usize, c: i64| self.permutations[axis][c.rem_euclid(PERIOD as i64) as usize];
+
+        itertools::iproduct!(0..2, 0..2, 0..2)
+            .map(|(i, j, k)| {
+                let corner = Vec3::new(i as f64, j as f64, k as f64);
+                let gradient = &self.gradients[index(0, x + i) ^ index(1, y + j) ^ index(2, z + k)];
+                let weight = corner.zip_map(&smooth, |c, s| c * s + (1.0 - c) * (1.0 - s));
+                weight.product() * gradient.dot(&(offset - corner))
+            })
+            .sum()
+    }
+
+    /// Fractal Brownian motion: `octaves` of noise, each twice as fine and
+    /// half as strong as the previous one.
+    pub fn fbm(&self, point: &Vec3, octaves: usize) -> f64 {
+        self.octaves(point, octaves, |noise| noise)
+    }
+
+    /// Like `fbm`, summing magnitudes for a billowy look in `[0, 1]`.
+    pub fn turbulence(&self, point: &Vec3, octaves: usize) -> f64 {
+        self.octaves(point, octaves, f64::abs)
+    }
+
+    fn octaves<F>(&self, point: &Vec3, octaves: usize, shape: F) -> f64
+    where
+        F: Fn(f64) -> f64,
+    {
+        let (mut sum, mut weight, mut point) = (0.0, 1.0, *point);
+        for _ in 0..octaves {
+            sum += weight * shape(self.noise(&point));
+            weight *= 0.5;
+            point *= 2.0;
+        }
+        sum
+    }
+}
+
+/// Cellular noise: one random feature point per unit cell.
+pub struct Worley {
+    seed: u64,
+}
+
+impl Worley {
+    pub fn new(seed: u64) -> Worley {
+        Worley { seed }
+    }
+
+    /// Feature point of cell `(i, j, k)`, hashed from the seed.
+    fn feature(&self, cell: [i64; 3]) -> Vec3 {
+        let mut hash = self.seed;
+        for c in cell {
+            hash = splitmix(hash ^ c as u64);
+        }
+        let offset = Vec3::new(
+            (hash & 0xffff) as f64,
+            ((hash >> 16) & 0xffff) as f64,
+            ((hash >> 32) & 0xffff) as f64,
+        ) / 65536.0;
+
+        Vec3::new(cell[0] as f64, cell[1] as f64, cell[2] as f64) + offset
+    }
+
+    /// Distances to the closest and second closest feature points.
+    pub fn distances(&self, point: &Vec3) -> (f64, f64) {
+        let cell = point.map(f64::floor);
+        let [x, y, z] = [cell.x, cell.y, cell.z].map(|c| c as i64);
+
+        let mut closest = (f64::INFINITY, f64::INFINITY);
+        for (i, j, k) in itertools::iproduct!(-1..=1, -1..=1, -1..=1) {
+            let distance = (self.feature([x + i, y + j, z + k]) - point).norm();
+            if distance < closest.0 {
+                closest = (distance, closest.0);
+            } else if distance < closest.1 {
+                closest.1 = distance;
+            }
+        }
+        closest
+    }
+}
+
+/// SplitMix64 finalizer, scrambling every bit of `x`.
+fn splitmix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Solid patterns, in `[0, 1]`.
+#[derive(Clone, Copy)]
+pub enum Pattern {
+    Perlin,
+    /// Turbulence of that many octaves.
+    Turbulence(usize),
+    /// Veins across z, bent by turbulence.
+    Marble,
+    /// Rings around the y axis, wobbled by noise.
+    Wood,
+    /// Distance to the closest feature point, dark at the cell centers.
+    Worley,
+    /// Gap between the two closest feature points, dark along cell borders.
+    Cells,
+}
+
+/// Resolution-independent texture blending two textures by a noise pattern
+/// of object space positions, black to white unless colored.
+///
+/// Driving scalar parameters, such as the fuzz of a `Metal` or the index of
+/// a `Dielectric`, it gives values from 0 to 1 unless colored.
+pub struct Noise {
+    pattern: Pattern,
+    perlin: Perlin,
+    worley: Worley,
+    /// Pattern features per unit.
+    frequency: f64,
+    low: Box<dyn Texture>,
+    high: Box<dyn Texture>,
+}
+
+impl Noise {
+    /// The same `seed` always gives the same pattern.
+    pub fn new(pattern: Pattern, seed: u64, frequency: f64) -> Noise {
+        Noise {
+            pattern,
+            perlin: Perlin::new(seed),
+            worley: Worley::new(seed),
+            frequency,
+            low: Vec3::zeros().boxed(),
+            high: Vec3::repeat(1.0).boxed(),
+        }
+    }
+
+    pub fn with_colors(
+        mut self,
+        low: impl Texture + 'static,
+        high: impl Texture + 'static,
+    ) -> Self {
+        self.low = low.boxed();
+        self.high = high.boxed();
+        self
+    }
+
+    fn level(&self, point: &Vec3) -> f64 {
+        let point = self.frequency * point;
+        let level = match self.pattern {
+            Pattern::Perlin => 0.5 * (1.0 + self.perlin.noise(&point)),
+            Pattern::Turbulence(octaves) => self.perlin.turbulence(&point, octaves),
+            Pattern::Marble => {
+                let phase = point.z + 10.0 * self.perlin.turbulence(&point, 7);
+                0.5 * (1.0 + phase.sin())
+            }
+            Pattern::Wood => {
+                let radius = point.xz().norm() + 0.2 * self.perlin.fbm(&point, 4);
+                0.5 * (1.0 - (TAU * radius).cos())
+            }
+            Pattern::Worley => self.worley.distances(&point).0,
+            Pattern::Cells => {
+                let (first, second) = self.worley.distances(&point);
+                2.0 * (second - first)
+            }
+        };
+        level.clamp(0.0, 1.0)
+    }
+}
+
+impl Texture for Noise {
+    fn value(&self, impact: &hit::Impact<'_>) -> Vec3 {
+        let level = self.level(&impact.local);
+        self.low.value(impact).lerp(&self.high.value(impact), level)
+    }
+}
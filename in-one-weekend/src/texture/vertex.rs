@@ -0,0 +1,23 @@
+use crate::hit;
+use crate::texture::Texture;
+use crate::Vec3;
+
+/// Colors of the mesh vertices, interpolated over their faces, or `fallback`
+/// on surfaces without any.
+pub struct VertexColor {
+    fallback: Box<dyn Texture>,
+}
+
+impl VertexColor {
+    pub fn new(fallback: impl Texture + 'static) -> VertexColor {
+        VertexColor {
+            fallback: fallback.boxed(),
+        }
+    }
+}
+
+impl Texture for VertexColor {
+    fn value(&self, impact: &hit::Impact<'_>) -> Vec3 {
+        impact.color.unwrap_or_else(|| self.fallback.value(impact))
+    }
+}